@@ -9,8 +9,9 @@ endif
 set nospell
 
 syn keyword alice_statement print println swap clear dup drop over rot
-syn keyword alice_statement let fun
-syn keyword alice_statement if else
+syn keyword alice_statement let fun object alias memoize
+syn keyword alice_statement if else while for in match not and or
+syn keyword alice_statement try catch
 
 syn match alice_ident '.*:(:?.*)'
 
@@ -19,14 +20,17 @@ syn match alice_float '\v\c<\d%(\d|_*\d)*%(E[+-]=\d%(\d|_*\d)*[FD]=|[FD])>'
 syn match alice_float '\v\c<\d%(\d|_*\d)*\.%(\d%(\d|_*\d)*)=%(E[+-]=\d%(\d|_*\d)*)=[FD]='
 syn match alice_float '\v\c\.\d%(\d|_*\d)*%(E[+-]=\d%(\d|_*\d)*)=[FD]='
 syn region alice_string start='"' end='"' skip="\\\""
+syn match alice_char "'\\\=.'"
 
 syn region alice_comment start=/#/ end=/$/
+syn region alice_comment start='/\*' end='\*/'
 
-syn keyword alice_type int float string bool any
+syn keyword alice_type int float string bool char list any
 syn keyword alice_const true false
 
 hi def link alice_statement     Keyword
 hi def link alice_string        String
+hi def link alice_char          Character
 hi def link alice_num           Number
 hi def link alice_float         Number
 hi def link alice_comment       Comment
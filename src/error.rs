@@ -0,0 +1,63 @@
+use crate::lexer::AliceLexerErr;
+use crate::loc::Loc;
+
+/// an error of any stage of running an alice program,
+/// displayed uniformly as `file:line:col: error <stage>: msg`
+#[derive(Debug)]
+pub struct AliceError {
+    pub stage: Stage,
+    pub msg: String,
+    /// line 0 if only the file is known
+    pub loc: Loc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Tokenizing,
+    Parsing,
+    Executing,
+}
+
+impl AliceError {
+    /// an error somewhere in the given file
+    pub fn in_file(stage: Stage, msg: String, file: String) -> Self {
+        Self {
+            stage,
+            msg,
+            loc: Loc::new(file, 0, 0),
+        }
+    }
+}
+
+impl From<AliceLexerErr> for AliceError {
+    fn from(err: AliceLexerErr) -> Self {
+        let (msg, loc) = match err {
+            AliceLexerErr::MissingDelimeter(msg, loc)
+            | AliceLexerErr::HitEOFWhileParsing(msg, loc)
+            | AliceLexerErr::IllegalEscapeSequence(msg, loc)
+            | AliceLexerErr::NumberFormatErr(msg, loc)
+            | AliceLexerErr::UnexpectedSymbol(msg, loc) => (msg, loc),
+        };
+        Self {
+            stage: Stage::Tokenizing,
+            msg,
+            loc,
+        }
+    }
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Tokenizing => write!(f, "tokenizing"),
+            Stage::Parsing => write!(f, "parsing"),
+            Stage::Executing => write!(f, "executing"),
+        }
+    }
+}
+
+impl std::fmt::Display for AliceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: error {}: {}", self.loc, self.stage, self.msg)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::runtime::AliceVal;
 use crate::statement::Statement;
 use std::rc::Rc;
 
@@ -5,8 +6,43 @@ use std::rc::Rc;
 // 1. if
 // 2. if-else
 // 3. match
+pub struct MatchContainer {
+    /// the body of the first arm whose pattern matches the matched value runs
+    pub arms: Vec<(MatchPattern, Vec<Rc<dyn Statement>>)>,
+    /// runs if no arm matches
+    pub default: Option<Vec<Rc<dyn Statement>>>,
+}
+
+/// what the matched value is compared to by a match arm
+#[derive(PartialEq)]
+pub enum MatchPattern {
+    /// matches values equal to the literal
+    Literal(AliceVal),
+    /// matches objects of the named type, binding their members in the arm body
+    Object(String),
+}
+
+impl std::fmt::Display for MatchPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Literal(val) => write!(f, "{val}"),
+            Self::Object(name) => write!(f, "{name}"),
+        }
+    }
+}
+
 // 4. for
+pub struct ForContainer {
+    /// bound to each int of the range while the body runs
+    pub var: String,
+    /// each pushes one int, the range includes start but not end
+    pub start: Vec<Rc<dyn Statement>>,
+    pub end: Vec<Rc<dyn Statement>>,
+    pub body: Vec<Rc<dyn Statement>>,
+}
+
 // 5. while
+// 6. try-catch
 
 /// 1. if
 #[derive(Clone)]
@@ -19,3 +55,17 @@ pub struct IfElseContainer {
     pub if_body: Vec<Rc<dyn Statement>>,
     pub else_body: Vec<Rc<dyn Statement>>,
 }
+
+// 5. while
+pub struct WhileContainer {
+    /// runs before every iteration and leaves the bool that decides whether to go on
+    pub cond: Vec<Rc<dyn Statement>>,
+    pub body: Vec<Rc<dyn Statement>>,
+}
+
+// 6. try-catch
+pub struct TryCatchContainer {
+    pub body: Vec<Rc<dyn Statement>>,
+    /// runs with the stack as it was before the body and the error message on top
+    pub handler: Vec<Rc<dyn Statement>>,
+}
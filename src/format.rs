@@ -0,0 +1,99 @@
+use crate::lexer::{glued, AliceLexer, AliceLexerErr, AliceSeparator, AliceToken, LocatedToken};
+
+/// indentation per level of nested blocks and lists
+const INDENT: &str = "    ";
+
+/// formats alice source code. line breaks stay where they are, so comments stay next
+/// to the statements they were written next to. each line is indented by how deeply
+/// nested its start is, tokens are separated by single spaces (see lexer::glued) and
+/// runs of blank lines are collapsed into one. literals, comments and the space before
+/// trailing comments are kept as written
+pub fn format(src: String, file: String) -> Result<String, AliceLexerErr> {
+    let tokens = AliceLexer::new(src.clone(), file)
+        .with_trivia()
+        .tokenize()?;
+    let mut out = String::with_capacity(src.len());
+    let mut depth = 0usize;
+    let mut blank = true;
+    for line in tokens.split(|t| matches!(t.token, AliceToken::Newline)) {
+        if line.is_empty() {
+            // a blank line is only kept between two non-blank lines
+            if !blank {
+                out.push('\n');
+                blank = true;
+            }
+            continue;
+        }
+        let closing = line.iter().take_while(|t| closes(&t.token)).count();
+        for _ in 0..depth.saturating_sub(closing) {
+            out.push_str(INDENT);
+        }
+        out.push_str(&format_line(&src, line));
+        out.push('\n');
+        blank = false;
+        for t in line {
+            if opens(&t.token) {
+                depth += 1;
+            } else if closes(&t.token) {
+                depth = depth.saturating_sub(1);
+            }
+        }
+    }
+    while out.ends_with("\n\n") {
+        out.pop();
+    }
+    Ok(out)
+}
+
+/// the tokens of one line as written, separated by single spaces where needed
+fn format_line(src: &str, line: &[LocatedToken]) -> String {
+    let mut s = String::new();
+    let mut prev: Option<&LocatedToken> = None;
+    for t in line {
+        match prev {
+            // keeps trailing comments aligned
+            Some(prev) if matches!(t.token, AliceToken::Comment(_)) => {
+                s.push_str(&src[prev.span.end..t.span.start])
+            }
+            Some(prev) if !glued(&prev.token, &t.token) => s.push(' '),
+            _ => {}
+        }
+        s.push_str(&src[t.span.clone()]);
+        prev = Some(t);
+    }
+    s
+}
+
+fn opens(token: &AliceToken) -> bool {
+    matches!(
+        token,
+        AliceToken::Sep(AliceSeparator::OpenB | AliceSeparator::OpenS | AliceSeparator::OpenP)
+    )
+}
+
+fn closes(token: &AliceToken) -> bool {
+    matches!(
+        token,
+        AliceToken::Sep(AliceSeparator::CloseB | AliceSeparator::CloseS | AliceSeparator::CloseP)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_stay_with_their_statements() {
+        let src = "# greets\n\"hi\"   println\ntrue if {\n/* inside */ 1 println   # one\n\n\n  2 println\n}\n";
+        let formatted = format(src.into(), "test".into()).ok().unwrap();
+        assert_eq!(
+            formatted,
+            "# greets\n\"hi\" println\ntrue if {\n    /* inside */ 1 println   # one\n\n    2 println\n}\n"
+        );
+        // formatting is idempotent
+        assert_eq!(
+            format(formatted.clone(), "test".into()).ok().unwrap(),
+            formatted
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use crate::error::{AliceError, Stage};
+use crate::lexer::{AliceLexer, AliceToken};
+use crate::object::AliceFun;
+use crate::parser::AliceParser;
+use crate::runtime::*;
+use crate::type_check::{check_statement, type_bit, TypeStack};
+use std::collections::HashMap;
+
+/// file name of the input in error messages
+const INTERACTIVE_FILE: &str = "<interactive>";
+
+/// state of an interactive session that persists between lines
+pub struct Interactive {
+    pub stack: AliceStack,
+    pub type_stack: TypeStack,
+    pub table: AliceTable,
+    pub ctx: AliceContext,
+    /// see AliceParser::pow_overflow_to_float
+    pub pow_overflow_to_float: bool,
+    /// aliases defined on previous lines, see AliceParser::aliases
+    pub aliases: HashMap<String, AliceToken>,
+    /// object types declared on previous lines, see AliceParser::object_types
+    pub object_types: HashMap<String, u32>,
+}
+
+impl Interactive {
+    /// a session running on the given runtime state, as configured by the command line
+    pub fn new(stack: AliceStack, table: AliceTable, ctx: AliceContext) -> Self {
+        Self {
+            stack,
+            type_stack: TypeStack::new(),
+            table,
+            ctx,
+            pow_overflow_to_float: false,
+            aliases: HashMap::new(),
+            object_types: HashMap::new(),
+        }
+    }
+
+    /// runs the read-eval-print loop until stdin is closed
+    pub fn run(&mut self) {
+        use std::io::Write;
+        println!("interactive alice");
+        let mut input = String::new();
+        loop {
+            print!("alice>>");
+            std::io::stdout().flush().expect("flushing stdout failed");
+            input.clear();
+            let read = std::io::stdin()
+                .read_line(&mut input)
+                .expect("reading stdin failed");
+            if read == 0 {
+                // EOF
+                println!();
+                return;
+            }
+            if let Err(e) = self.eval_line(input.trim().into()) {
+                eprintln!("{e}");
+            }
+        }
+    }
+
+    /// tokenizes, type checks and executes a single line of input, one statement at a time.
+    /// each statement is type checked against a copy of the persistent type stack, which is
+    /// only kept if the statement checks out. the first bad statement stops the line, but
+    /// the statements before it have already run
+    pub fn eval_line(&mut self, line: String) -> Result<(), String> {
+        if let Some(command) = line.strip_prefix(':') {
+            return self.eval_command(command);
+        }
+        let tokens = AliceLexer::new(line, INTERACTIVE_FILE.into())
+            .tokenize()
+            .map_err(|e| AliceError::from(e).to_string())?;
+        let parser = AliceParser::new(tokens)
+            .pow_overflow_to_float(self.pow_overflow_to_float)
+            .with_aliases(std::mem::take(&mut self.aliases))
+            .with_object_types(std::mem::take(&mut self.object_types));
+        let result = parser.parse_each(
+            |e| e.to_string(),
+            |s| {
+                let mut type_stack = self.type_stack.clone();
+                let checked = check_statement(&mut type_stack, s.as_ref());
+                for warning in self.type_stack.warnings.take() {
+                    eprintln!("{INTERACTIVE_FILE}: warning: {warning}");
+                }
+                checked.map_err(|e| e.in_file(INTERACTIVE_FILE.into()).to_string())?;
+                self.type_stack = type_stack;
+                if let Err(e) = s.run(&mut self.stack, &mut self.table, &mut self.ctx) {
+                    // the statement may have stopped halfway, so the checked types are off
+                    self.sync_type_stack();
+                    return Err(
+                        AliceError::in_file(Stage::Executing, e, INTERACTIVE_FILE.into())
+                            .to_string(),
+                    );
+                }
+                Ok(())
+            },
+        );
+        self.aliases = parser.aliases();
+        self.object_types = parser.object_types();
+        result
+    }
+
+    /// commands that aren't alice code:
+    /// - `:save <path>` writes the source of all functions defined so far to a file
+    /// - `:load <path>` evaluates a file in this session
+    /// - `:doc <name>` prints the doc comment of a function
+    fn eval_command(&mut self, command: &str) -> Result<(), String> {
+        let (name, arg) = match command.split_once(' ') {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (command, ""),
+        };
+        match name {
+            "save" | "load" if arg.is_empty() => Err(format!(":{name} requires a path")),
+            "doc" => match self.table.vars.get(arg) {
+                Some(AliceVal::Function(Some(fun))) => match &fun.doc {
+                    Some(doc) => {
+                        println!("{doc}");
+                        Ok(())
+                    }
+                    None => Err(format!("function '{arg}' is not documented")),
+                },
+                _ => Err(format!("there is no function named '{arg}'")),
+            },
+            "save" => {
+                self.ctx.require_io(":save")?;
+                let mut funs: Vec<(&String, &AliceFun)> = self
+                    .table
+                    .vars
+                    .iter()
+                    .filter_map(|(ident, val)| match val {
+                        AliceVal::Function(Some(fun)) => Some((ident, fun)),
+                        _ => None,
+                    })
+                    .collect();
+                funs.sort_by_key(|(ident, _)| *ident);
+                let mut src = String::new();
+                for (_, fun) in funs {
+                    if let Some(fun_src) = &fun.source {
+                        src.push_str(fun_src);
+                        src.push('\n');
+                    }
+                }
+                self.ctx.fs.write(arg, &src)
+            }
+            "load" => {
+                self.ctx.require_io(":load")?;
+                let src = self.ctx.fs.read(arg)?;
+                self.eval_line(src)
+            }
+            _ => Err(format!("unknown command :{name}")),
+        }
+    }
+
+    /// rebuilds the type stack values from the actual runtime stack
+    fn sync_type_stack(&mut self) {
+        self.type_stack.vals.clear();
+        for val in &self.stack.stack {
+            self.type_stack.vals.push(type_bit(val));
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use crate::object::{AliceObj, AliceObjType};
+use crate::runtime::*;
+use crate::type_check::{is_object, prototype};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// serializes the whole stack (bottom first) into a json array of type-tagged values:
+/// [{"type": "int", "value": 3}, ...]
+pub fn tagged_stack(stack: &AliceStack) -> String {
+    let vals: Vec<String> = stack.stack.iter().map(tagged).collect();
+    format!("[{}]", vals.join(","))
+}
+
+/// serializes a value into a json object tagged with its type name
+pub fn tagged(val: &AliceVal) -> String {
+    let value = match val {
+        AliceVal::List(Some(l)) => {
+            let vals: Vec<String> = l.vals.iter().map(tagged).collect();
+            format!("[{}]", vals.join(","))
+        }
+        AliceVal::Object(Some(o)) => members(o, tagged),
+        _ => primitive(val),
+    };
+    format!(
+        "{{\"type\":{},\"value\":{value}}}",
+        string(&val.type_name())
+    )
+}
+
+/// serializes a non-container value; null bindings and non-finite floats become null
+fn primitive(val: &AliceVal) -> String {
+    match val {
+        AliceVal::String(Some(s)) => string(s),
+        AliceVal::Char(Some(c)) => string(&c.to_string()),
+        AliceVal::Bool(Some(b)) => b.to_string(),
+        AliceVal::Int(Some(i)) => i.to_string(),
+        AliceVal::Float(Some(f)) if f.is_finite() => format!("{f:?}"),
+        _ => "null".into(),
+    }
+}
+
+/// a quoted and escaped json string
+pub fn string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// the members of an object as a json object in sorted key order
+fn members(o: &AliceObj, serialize: fn(&AliceVal) -> String) -> String {
+    let mut names: Vec<&String> = o.members.keys().collect();
+    names.sort();
+    let members: Vec<String> = names
+        .into_iter()
+        .map(|name| format!("{}:{}", string(name), serialize(&o.members[name])))
+        .collect();
+    format!("{{{}}}", members.join(","))
+}
+
+/// serializes a value into plain json: lists become arrays and objects become
+/// {"type": "Name", "value": {"member": ..., ...}}
+pub fn plain(val: &AliceVal) -> String {
+    match val {
+        AliceVal::List(Some(l)) => {
+            let vals: Vec<String> = l.vals.iter().map(plain).collect();
+            format!("[{}]", vals.join(","))
+        }
+        AliceVal::Object(Some(o)) => format!(
+            "{{\"type\":{},\"value\":{}}}",
+            string(&o.type_name),
+            members(o, plain)
+        ),
+        _ => primitive(val),
+    }
+}
+
+/// parses json into a value of the same type as the given prototype
+pub fn parse_as(src: &str, target: &AliceVal) -> Result<AliceVal, String> {
+    let mut parser = JsonParser::new(src);
+    let json = parser.value()?;
+    parser.skip_whitespace();
+    if let Some(c) = parser.chars.next() {
+        return Err(format!("invalid json: unexpected '{c}' after the value"));
+    }
+    json.into_val(target)
+}
+
+/// parses json as written by `plain` into an object of the given type.
+/// members of object types are looked up in the declared object types
+pub fn parse_object(
+    src: &str,
+    ty: &AliceObjType,
+    objects: &HashMap<String, Rc<AliceObjType>>,
+) -> Result<AliceVal, String> {
+    let mut parser = JsonParser::new(src);
+    let json = parser.value()?;
+    parser.skip_whitespace();
+    if let Some(c) = parser.chars.next() {
+        return Err(format!("invalid json: unexpected '{c}' after the value"));
+    }
+    json.into_object(ty, objects)
+}
+
+/// a parsed json value before it is matched against an alice type
+enum Json {
+    Null,
+    Bool(bool),
+    /// the literal, so that ints don't go through f64
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    /// in source order
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn kind(&self) -> &'static str {
+        match self {
+            Json::Null => "null",
+            Json::Bool(_) => "a boolean",
+            Json::Number(_) => "a number",
+            Json::String(_) => "a string",
+            Json::Array(_) => "an array",
+            Json::Object(_) => "an object",
+        }
+    }
+
+    fn into_val(self, target: &AliceVal) -> Result<AliceVal, String> {
+        Ok(match (self, target) {
+            (Json::Bool(b), AliceVal::Bool(_)) => AliceVal::Bool(Some(b)),
+            (Json::String(s), AliceVal::String(_)) => AliceVal::String(Some(s)),
+            (Json::String(s), AliceVal::Char(_)) => AliceVal::String(Some(s)).convert(target)?,
+            (Json::Number(n), AliceVal::Int(_)) => AliceVal::Int(Some(
+                n.parse()
+                    .map_err(|_| format!("json number {n} is not an int"))?,
+            )),
+            (Json::Number(n), AliceVal::Float(_)) => AliceVal::Float(Some(
+                n.parse()
+                    .map_err(|_| format!("json number {n} is not a float"))?,
+            )),
+            (Json::Array(items), AliceVal::List(Some(l))) => {
+                let elem = crate::type_check::prototype(l.elem_type)?;
+                let vals = items
+                    .into_iter()
+                    .map(|item| item.into_val(&elem))
+                    .collect::<Result<Vec<_>, _>>()?;
+                AliceVal::list(l.elem_type, vals)
+            }
+            (json, target) => {
+                return Err(format!(
+                    "expected json for {}, found {}",
+                    crate::type_check::type_name(crate::type_check::type_bit(target)),
+                    json.kind()
+                ))
+            }
+        })
+    }
+
+    /// an object of the given type from {"type": "Name", "value": {"member": ..., ...}}.
+    /// every member has to be present; null gives a member without a value
+    fn into_object(
+        self,
+        ty: &AliceObjType,
+        objects: &HashMap<String, Rc<AliceObjType>>,
+    ) -> Result<AliceVal, String> {
+        let fields = match self {
+            Json::Object(fields) => fields,
+            json => {
+                return Err(format!(
+                    "expected json object for {}, found {}",
+                    ty.name,
+                    json.kind()
+                ))
+            }
+        };
+        let mut type_name = None;
+        let mut value = None;
+        for (key, json) in fields {
+            match (key.as_str(), json) {
+                ("type", Json::String(name)) => type_name = Some(name),
+                ("value", Json::Object(members)) => value = Some(members),
+                (key, _) => return Err(format!("unexpected json field '{key}' for {}", ty.name)),
+            }
+        }
+        match type_name {
+            Some(name) if name == ty.name => {}
+            Some(name) => return Err(format!("expected json for {}, found {name}", ty.name)),
+            None => return Err(format!("json for {} is missing its \"type\"", ty.name)),
+        }
+        let mut json_members: HashMap<String, Json> = value
+            .ok_or_else(|| format!("json for {} is missing its \"value\"", ty.name))?
+            .into_iter()
+            .collect();
+        let mut members = HashMap::with_capacity(ty.members.len());
+        for (name, member_ty) in &ty.members {
+            let val = match json_members.remove(name) {
+                Some(Json::Null) if is_object(member_ty) => AliceVal::Object(None),
+                Some(Json::Null) => prototype(*member_ty)?,
+                Some(json) if is_object(member_ty) => {
+                    let member_obj_ty = objects
+                        .values()
+                        .find(|obj_ty| obj_ty.type_hash == *member_ty)
+                        .ok_or_else(|| format!("unknown type of member {name} of {}", ty.name))?;
+                    json.into_object(member_obj_ty, objects)
+                        .map_err(|e| format!("member {name} of {}: {e}", ty.name))?
+                }
+                Some(json) => json
+                    .into_val(&prototype(*member_ty)?)
+                    .map_err(|e| format!("member {name} of {}: {e}", ty.name))?,
+                None => match ty.defaults.get(name) {
+                    Some(default) => default.clone(),
+                    None => return Err(format!("json for {} is missing member {name}", ty.name)),
+                },
+            };
+            members.insert(name.clone(), val);
+        }
+        if let Some(name) = json_members.keys().next() {
+            return Err(format!("{} has no member {name}", ty.name));
+        }
+        Ok(ty.instantiate(members))
+    }
+}
+
+/// how deeply json arrays and objects may be nested
+pub const MAX_JSON_DEPTH: usize = 256;
+
+struct JsonParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    /// arrays and objects around the current value
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(src: &'a str) -> Self {
+        Self {
+            chars: src.chars().peekable(),
+            depth: 0,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some(c) if c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, word: &str) -> Result<(), String> {
+        for expected in word.chars() {
+            if self.chars.next() != Some(expected) {
+                return Err(format!("invalid json: expected '{word}'"));
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        if matches!(self.chars.peek(), Some('[' | '{')) {
+            if self.depth == MAX_JSON_DEPTH {
+                return Err(format!(
+                    "invalid json: nested deeper than {MAX_JSON_DEPTH} levels"
+                ));
+            }
+            self.depth += 1;
+            let nested = self.nested();
+            self.depth -= 1;
+            return nested;
+        }
+        match self.chars.peek() {
+            None => Err("invalid json: unexpected end of input".into()),
+            Some('n') => self.expect("null").map(|_| Json::Null),
+            Some('t') => self.expect("true").map(|_| Json::Bool(true)),
+            Some('f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some(c) if *c == '-' || c.is_ascii_digit() => {
+                let mut n = String::new();
+                while let Some(c) = self.chars.peek() {
+                    if c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E') {
+                        n.push(*c);
+                        self.chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                Ok(Json::Number(n))
+            }
+            Some(c) => Err(format!("invalid json: unexpected '{c}'")),
+        }
+    }
+
+    /// an array or object, the next char being its opening bracket
+    fn nested(&mut self) -> Result<Json, String> {
+        match self.chars.peek() {
+            Some('[') => {
+                self.chars.next();
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.chars.peek() == Some(&']') {
+                    self.chars.next();
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.chars.next() {
+                        Some(',') => continue,
+                        Some(']') => return Ok(Json::Array(items)),
+                        _ => return Err("invalid json: expected ',' or ']' in array".into()),
+                    }
+                }
+            }
+            Some('{') => {
+                self.chars.next();
+                self.skip_whitespace();
+                let mut fields = Vec::new();
+                if self.chars.peek() != Some(&'}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.skip_whitespace();
+                        self.expect(":")?;
+                        fields.push((key, self.value()?));
+                        self.skip_whitespace();
+                        match self.chars.next() {
+                            Some(',') => continue,
+                            Some('}') => break,
+                            _ => return Err("invalid json: expected ',' or '}' in object".into()),
+                        }
+                    }
+                } else {
+                    self.chars.next();
+                }
+                Ok(Json::Object(fields))
+            }
+            _ => unreachable!("nested is only called on '[' or '{{'"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.chars.next() != Some('"') {
+            return Err("invalid json: expected a string".into());
+        }
+        let mut s = String::new();
+        loop {
+            match self.chars.next() {
+                None => return Err("invalid json: unterminated string".into()),
+                Some('"') => return Ok(s),
+                Some('\\') => match self.chars.next() {
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some('b') => s.push('\u{8}'),
+                    Some('f') => s.push('\u{c}'),
+                    Some('u') => {
+                        let hex: String = self.chars.by_ref().take(4).collect();
+                        let c = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("invalid json: bad escape \\u{hex}"))?;
+                        s.push(c);
+                    }
+                    Some(c @ ('"' | '\\' | '/')) => s.push(c),
+                    _ => return Err("invalid json: bad escape sequence".into()),
+                },
+                Some(c) => s.push(c),
+            }
+        }
+    }
+}
@@ -6,6 +6,17 @@ pub const KW_TRUE: &str = "true";
 pub const KW_FALSE: &str = "false";
 pub const KW_IF: &str = "if";
 pub const KW_ELSE: &str = "else";
+pub const KW_TRY: &str = "try";
+pub const KW_CATCH: &str = "catch";
+pub const KW_ALIAS: &str = "alias";
+pub const KW_MEMOIZE: &str = "memoize";
+pub const KW_NOT: &str = "not";
+pub const KW_AND: &str = "and";
+pub const KW_OR: &str = "or";
+pub const KW_WHILE: &str = "while";
+pub const KW_FOR: &str = "for";
+pub const KW_MATCH: &str = "match";
+pub const KW_OBJECT: &str = "object";
 
 pub enum Keyword {
     Let,
@@ -14,6 +25,17 @@ pub enum Keyword {
     False,
     If,
     Else,
+    Try,
+    Catch,
+    Alias,
+    Memoize,
+    Not,
+    And,
+    Or,
+    While,
+    For,
+    Match,
+    Object,
 }
 
 pub fn keywords() -> HashMap<String, Keyword> {
@@ -24,5 +46,16 @@ pub fn keywords() -> HashMap<String, Keyword> {
     kws.insert(KW_FALSE.into(), Keyword::False);
     kws.insert(KW_IF.into(), Keyword::If);
     kws.insert(KW_ELSE.into(), Keyword::Else);
+    kws.insert(KW_TRY.into(), Keyword::Try);
+    kws.insert(KW_CATCH.into(), Keyword::Catch);
+    kws.insert(KW_ALIAS.into(), Keyword::Alias);
+    kws.insert(KW_MEMOIZE.into(), Keyword::Memoize);
+    kws.insert(KW_NOT.into(), Keyword::Not);
+    kws.insert(KW_AND.into(), Keyword::And);
+    kws.insert(KW_OR.into(), Keyword::Or);
+    kws.insert(KW_WHILE.into(), Keyword::While);
+    kws.insert(KW_FOR.into(), Keyword::For);
+    kws.insert(KW_MATCH.into(), Keyword::Match);
+    kws.insert(KW_OBJECT.into(), Keyword::Object);
     kws
 }
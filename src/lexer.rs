@@ -1,30 +1,48 @@
-use std::iter::Peekable;
 use std::num::{ParseFloatError, ParseIntError};
-use std::str::Chars;
 
 use crate::loc::Loc;
+use crate::utils::strip_digit_separators;
 
 #[derive(Debug)]
 pub struct AliceLexer {
     src: String,
     loc: Loc,
+    /// see AliceLexer::with_trivia
+    trivia: bool,
 }
 
 // when adding a new item, must modify all places comment-marked:
 // on_add_token
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AliceToken {
     IdentOrKeyw(String),
     String(String),
-    /// number as an f64 and true if the literal contained a decimal part
+    Char(char),
+    /// the text of a `/** ... */` doc comment, see AliceLexer::doc_text
+    Doc(String),
+    /// a comment including its delimiters, only lexed with trivia
+    Comment(String),
+    /// a line break, only lexed with trivia
+    Newline,
+    /// number as an f64 and true if the literal contained a decimal part or an exponent,
+    /// i. e. whether it is a float unless converted
     Number(f64, bool),
     Sep(AliceSeparator),
     Op(AliceOp),
 }
 
+/// a token and the location of its first char
+#[derive(Debug)]
+pub struct LocatedToken {
+    pub token: AliceToken,
+    pub loc: Loc,
+    /// byte range of the token in the source
+    pub span: std::ops::Range<usize>,
+}
+
 // when adding a new item, must modify all places comment-marked:
 // on_add_sep
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AliceSeparator {
     OpenP,  // (
     CloseP, // )
@@ -34,6 +52,7 @@ pub enum AliceSeparator {
     CloseS, // ]
     Comma,  // ,
     Period, // .
+    Range,  // ..
     Colon,  // :
     Semi,   // ;
     At,     // @
@@ -41,7 +60,7 @@ pub enum AliceSeparator {
 
 // when adding a new item, must modify all places comment-marked:
 // on_add_op
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AliceOp {
     Add,
     Sub,
@@ -64,62 +83,173 @@ pub enum AliceLexerErr {
     UnexpectedSymbol(String, Loc),
 }
 
+/// the chars of the source, keeping track of the byte offset of the next char
+/// so that locations can be derived
+#[derive(Debug, Clone)]
+pub struct SrcChars<'a> {
+    src: &'a str,
+    offset: usize,
+    next: Option<char>,
+}
+
+impl<'a> SrcChars<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            offset: 0,
+            next: src.chars().next(),
+        }
+    }
+
+    pub fn peek(&self) -> Option<&char> {
+        self.next.as_ref()
+    }
+
+    /// byte offset of the next char
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Iterator for SrcChars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.next?;
+        self.offset += c.len_utf8();
+        self.next = self.src[self.offset..].chars().next();
+        Some(c)
+    }
+}
+
 impl AliceLexer {
     pub fn new(src: String, file: String) -> Self {
         Self {
             src,
             loc: Loc::new(file, 1, 1),
+            trivia: false,
         }
     }
 
-    pub fn tokenize(&mut self) -> Result<Vec<AliceToken>, AliceLexerErr> {
-        let mut tokens = Vec::<AliceToken>::new();
-        let mut char_iter = self.src.chars().peekable();
+    /// also lex comments and line breaks, which are skipped otherwise, so that the
+    /// source can be rebuilt from the tokens, see format::format.
+    /// doc comments become plain comments then
+    pub fn with_trivia(mut self) -> Self {
+        self.trivia = true;
+        self
+    }
+
+    pub fn tokenize(&mut self) -> Result<Vec<LocatedToken>, AliceLexerErr> {
+        let mut tokens = Vec::<LocatedToken>::new();
+        let mut char_iter = SrcChars::new(&self.src);
+        // self.loc is the location of the char at this offset
+        let mut loc_offset = 0;
         while let Some(c) = char_iter.next() {
+            let start = char_iter.offset() - c.len_utf8();
+            if c == '\n' && self.trivia {
+                tokens.push(LocatedToken {
+                    token: AliceToken::Newline,
+                    loc: self.loc.clone(),
+                    span: start..char_iter.offset(),
+                });
+            }
             if c.is_whitespace() {
+                continue;
+            }
+            // errors are reported at the start of the token or comment
+            for c in self.src[loc_offset..start].chars() {
                 if c == '\n' {
                     self.loc.line += 1;
                     self.loc.column = 1;
                 } else {
                     self.loc.column += 1;
                 }
-                continue;
             }
+            loc_offset = start;
             if c == '#' {
-                while let Some(c) = char_iter.next() {
-                    if c == '\n' {
-                        self.loc.line += 1;
-                        self.loc.column = 1;
-                        break;
-                    }
+                while char_iter.peek().is_some_and(|c| *c != '\n') {
+                    char_iter.next();
+                }
+                if self.trivia {
+                    tokens.push(LocatedToken {
+                        token: AliceToken::Comment(self.src[start..char_iter.offset()].into()),
+                        loc: self.loc.clone(),
+                        span: start..char_iter.offset(),
+                    });
+                }
+                continue;
+            }
+            if c == '/' && char_iter.peek() == Some(&'*') {
+                char_iter.next();
+                // /** starts a doc comment, but /**/ is an empty plain comment
+                let mut lookahead = char_iter.clone();
+                let is_doc = lookahead.next() == Some('*') && lookahead.peek() != Some(&'/');
+                let text = self.gobble_block_comment(&mut char_iter)?;
+                let span = start..char_iter.offset();
+                if self.trivia {
+                    tokens.push(LocatedToken {
+                        token: AliceToken::Comment(self.src[span.clone()].into()),
+                        loc: self.loc.clone(),
+                        span,
+                    });
+                } else if is_doc {
+                    tokens.push(LocatedToken {
+                        token: AliceToken::Doc(doc_text(&text[1..])),
+                        loc: self.loc.clone(),
+                        span,
+                    });
                 }
                 continue;
             }
             let token = self.gobble_token(c, &mut char_iter)?;
-            tokens.push(token);
+            tokens.push(LocatedToken {
+                token,
+                loc: self.loc.clone(),
+                span: start..char_iter.offset(),
+            });
         }
         Ok(tokens)
     }
 
-    fn gobble_token(
-        &self,
-        start: char,
-        iter: &mut Peekable<Chars>,
-    ) -> Result<AliceToken, AliceLexerErr> {
+    /// gobbles a `/* ... */` comment, which may contain nested block comments, and returns
+    /// the text between the outermost delimiters; `iter` is right after the opening `/*`
+    fn gobble_block_comment(&self, iter: &mut SrcChars) -> Result<String, AliceLexerErr> {
+        let mut depth = 1;
+        let mut text = String::new();
+        while let Some(c) = iter.next() {
+            if c == '*' && iter.peek() == Some(&'/') {
+                iter.next();
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(text);
+                }
+                text.push_str("*/");
+            } else if c == '/' && iter.peek() == Some(&'*') {
+                iter.next();
+                depth += 1;
+                text.push_str("/*");
+            } else {
+                text.push(c);
+            }
+        }
+        Err(AliceLexerErr::HitEOFWhileParsing(
+            "unterminated block comment, missing '*/'".into(),
+            self.loc.clone(),
+        ))
+    }
+
+    fn gobble_token(&self, start: char, iter: &mut SrcChars) -> Result<AliceToken, AliceLexerErr> {
         match start {
-            '"' | '\'' => self.gobble_string(start, iter),
-            n if n.is_digit(10) => self.gobble_number(start, iter),
+            '"' => self.gobble_string(start, iter),
+            '\'' => self.gobble_char(iter),
+            n if n.is_ascii_digit() => self.gobble_number(start, iter),
             o if AliceOp::contains(&o) => self.gobble_operator(start, iter),
             s if AliceSeparator::contains(&s) => self.gobble_separator(s, iter),
             _ => self.gobble_ident_or_keyw(start, iter),
         }
     }
 
-    fn gobble_string(
-        &self,
-        end: char,
-        iter: &mut Peekable<Chars>,
-    ) -> Result<AliceToken, AliceLexerErr> {
+    fn gobble_string(&self, end: char, iter: &mut SrcChars) -> Result<AliceToken, AliceLexerErr> {
         let mut s = String::new();
         let mut escaped = false;
         while let Some(c) = iter.peek() {
@@ -130,6 +260,12 @@ impl AliceLexer {
                     'n' => s.push('\n'),
                     'r' => s.push('\r'),
                     't' => s.push('\t'),
+                    'x' | 'u' => {
+                        iter.next();
+                        s.push(self.gobble_code_escape(c, iter)?);
+                        escaped = false;
+                        continue;
+                    }
                     _ => {
                         return Err(AliceLexerErr::IllegalEscapeSequence(
                             format!("unknown escape sequence \\{c}"),
@@ -154,19 +290,95 @@ impl AliceLexer {
         ))
     }
 
-    fn gobble_number(
-        &self,
-        start: char,
-        iter: &mut Peekable<Chars>,
-    ) -> Result<AliceToken, AliceLexerErr> {
+    /// a char literal is written like a string literal with single quotes,
+    /// but has to contain exactly one character, e. g. 'a' or '\n'
+    fn gobble_char(&self, iter: &mut SrcChars) -> Result<AliceToken, AliceLexerErr> {
+        let s = match self.gobble_string('\'', iter)? {
+            AliceToken::String(s) => s,
+            _ => unreachable!("gobble_string only returns strings"),
+        };
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(AliceToken::Char(c)),
+            _ => Err(AliceLexerErr::UnexpectedSymbol(
+                format!(
+                    "char literal '{s}' must contain exactly one character, strings are written like \"{s}\""
+                ),
+                self.loc.clone(),
+            )),
+        }
+    }
+
+    /// the character of a `\x41` or `\u{41}` escape; `iter` is right after the x or u
+    fn gobble_code_escape(&self, kind: char, iter: &mut SrcChars) -> Result<char, AliceLexerErr> {
+        let illegal = |msg: String| AliceLexerErr::IllegalEscapeSequence(msg, self.loc.clone());
+        let digits: String = if kind == 'x' {
+            iter.by_ref().take(2).collect()
+        } else {
+            if iter.next() != Some('{') {
+                return Err(illegal("expected '{' after \\u".into()));
+            }
+            let mut digits = String::new();
+            loop {
+                match iter.next() {
+                    Some('}') => break,
+                    Some(c) => digits.push(c),
+                    None => return Err(illegal("missing '}' after \\u{".into())),
+                }
+            }
+            digits
+        };
+        let max_len = if kind == 'x' { 2 } else { 6 };
+        if digits.is_empty()
+            || digits.len() > max_len
+            || !digits.chars().all(|c| c.is_ascii_hexdigit())
+            || (kind == 'x' && digits.len() != 2)
+        {
+            return Err(illegal(format!(
+                "invalid escape sequence \\{kind}{digits}: expected {}",
+                if kind == 'x' {
+                    "two hex digits like \\x41"
+                } else {
+                    "one to six hex digits like \\u{41}"
+                }
+            )));
+        }
+        // unwrapping safe due to the hex digit check
+        let code = u32::from_str_radix(&digits, 16).unwrap();
+        if kind == 'x' && code > 0x7F {
+            return Err(illegal(format!(
+                "invalid escape sequence \\x{digits}: \\x only allows ascii, use \\u{{{digits}}}"
+            )));
+        }
+        char::from_u32(code).ok_or_else(|| {
+            illegal(format!(
+                "invalid escape sequence \\u{{{digits}}}: not a unicode scalar value"
+            ))
+        })
+    }
+
+    /// a leading 0 is either followed by a base hint (0x, 0o, 0b) or the literal is decimal;
+    /// leading zeros don't change the base, i. e. 007 is 7
+    fn gobble_number(&self, start: char, iter: &mut SrcChars) -> Result<AliceToken, AliceLexerErr> {
         let mut had_period = start == '.';
+        let mut had_exponent = false;
         let mut s = String::new();
         s.push(start);
         let base = if start == '0' {
+            if at_range(iter) {
+                return Ok(AliceToken::Number(0.0, false));
+            }
+            // a separator right after the 0 belongs to the next token
+            if matches!(iter.peek(), Some(b) if *b != '.' && (is_token_separator(b) || b.is_whitespace()))
+            {
+                return Ok(AliceToken::Number(0.0, false));
+            }
             match iter.next() {
-                Some(b) if b == 'x' => 16,
-                Some(b) if b == 'b' => 2,
-                Some(b) if b == '_' || b.is_digit(10) => {
+                Some('x') => 16,
+                Some('o') => 8,
+                Some('b') => 2,
+                // leading zeros
+                Some(b) if b == '_' || b.is_ascii_digit() => {
                     s.push(b);
                     10
                 }
@@ -175,8 +387,11 @@ impl AliceLexer {
                     had_period = true;
                     10
                 }
-                Some(b) if is_token_separator(&b) || b.is_whitespace() => {
-                    return Ok(AliceToken::Number(0.0, false))
+                Some(e) if e == 'e' || e == 'E' => {
+                    s.push(e);
+                    self.gobble_exponent(&mut s, iter)?;
+                    had_exponent = true;
+                    10
                 }
                 Some(b) => {
                     return Err(AliceLexerErr::NumberFormatErr(
@@ -192,9 +407,21 @@ impl AliceLexer {
         while let Some(c) = iter.peek() {
             match *c {
                 d if d.is_digit(base) => s.push(d),
-                '_' => (),
+                '_' => s.push('_'),
+                // the number is the start of a range like 0..10
+                '.' if at_range(iter) => break,
                 '.' => {
-                    if had_period {
+                    if base != 10 {
+                        return Err(AliceLexerErr::NumberFormatErr(
+                            format!("fractional {} literals are not supported", base_name(base)),
+                            self.loc.clone(),
+                        ));
+                    } else if had_exponent {
+                        return Err(AliceLexerErr::NumberFormatErr(
+                            "period in the exponent of a number literal!".into(),
+                            self.loc.clone(),
+                        ));
+                    } else if had_period {
                         return Err(AliceLexerErr::NumberFormatErr(
                             "multiple period in number literal!".into(),
                             self.loc.clone(),
@@ -204,10 +431,38 @@ impl AliceLexer {
                         had_period = true;
                     }
                 }
+                e if (e == 'e' || e == 'E') && base == 10 && !had_exponent => {
+                    s.push(e);
+                    iter.next();
+                    self.gobble_exponent(&mut s, iter)?;
+                    had_exponent = true;
+                    continue;
+                }
                 c if is_token_separator(&c) || c.is_whitespace() => {
                     return Ok(AliceToken::Number(
                         self.parse_number(s, base as u32)?,
-                        had_period,
+                        had_period || had_exponent,
+                    ));
+                }
+                c if c.is_alphabetic() => {
+                    // e. g. 5int or 5.0f; report the whole word rather than a single symbol
+                    let literal = match base {
+                        16 => format!("0x{}", &s[1..]),
+                        8 => format!("0o{}", &s[1..]),
+                        2 => format!("0b{}", &s[1..]),
+                        _ => s.clone(),
+                    };
+                    let mut word = literal.clone();
+                    while let Some(c) = iter.peek() {
+                        if is_token_separator(c) || c.is_whitespace() {
+                            break;
+                        }
+                        word.push(*c);
+                        iter.next();
+                    }
+                    return Err(AliceLexerErr::NumberFormatErr(
+                        format!("invalid number literal '{word}': numbers must be followed by whitespace or a separator, conversions are written like {literal}@int"),
+                        self.loc.clone(),
                     ));
                 }
                 _ => {
@@ -221,11 +476,36 @@ impl AliceLexer {
         }
         Ok(AliceToken::Number(
             self.parse_number(s, base as u32)?,
-            had_period,
+            had_period || had_exponent,
         ))
     }
 
+    /// gobbles the optional sign of an exponent after its e, which has to be followed by
+    /// at least one digit. the digits themselves are left to gobble_number
+    fn gobble_exponent(&self, s: &mut String, iter: &mut SrcChars) -> Result<(), AliceLexerErr> {
+        if let Some(sign) = iter.peek().copied().filter(|c| *c == '+' || *c == '-') {
+            s.push(sign);
+            iter.next();
+        }
+        match iter.peek() {
+            Some(d) if d.is_ascii_digit() => Ok(()),
+            _ => Err(AliceLexerErr::NumberFormatErr(
+                format!("missing digits in the exponent of number literal '{s}'"),
+                self.loc.clone(),
+            )),
+        }
+    }
+
+    /// `s` starts with the leading 0 of the base hint if the base isn't 10
     fn parse_number(&self, s: String, base: u32) -> Result<f64, AliceLexerErr> {
+        if base != 10 && !s[1..].chars().any(|c| c.is_digit(base)) {
+            return Err(AliceLexerErr::NumberFormatErr(
+                format!("{} literal without digits", base_name(base)),
+                self.loc.clone(),
+            ));
+        }
+        let s = strip_digit_separators(&s, base)
+            .map_err(|e| AliceLexerErr::NumberFormatErr(e, self.loc.clone()))?;
         if base == 10 {
             s.parse()
                 .map_err(|e: ParseFloatError| to_number_format_error(e, self.loc.clone()))
@@ -240,13 +520,21 @@ impl AliceLexer {
     fn gobble_operator(
         &self,
         start: char,
-        iter: &mut Peekable<Chars>,
+        iter: &mut SrcChars,
     ) -> Result<AliceToken, AliceLexerErr> {
         if let Some(next) = iter.peek() {
             if start == '*' && *next == '*' {
                 iter.next();
                 return Ok(AliceToken::Op(AliceOp::Pow));
             }
+            // a minus directly followed by a digit is a negative number literal.
+            // the sign is applied after parsing, so that it works for every base: -0xFF
+            if start == '-' && next.is_ascii_digit() {
+                let first = iter.next().unwrap(); // unwrapping safe due to peek
+                if let AliceToken::Number(n, dec) = self.gobble_number(first, iter)? {
+                    return Ok(AliceToken::Number(-n, dec));
+                }
+            }
         }
         Ok(AliceToken::Op(start.into()))
     }
@@ -255,9 +543,13 @@ impl AliceLexer {
     fn gobble_separator(
         &self,
         sep: char,
-        _iter: &mut Peekable<Chars>,
+        iter: &mut SrcChars,
     ) -> Result<AliceToken, AliceLexerErr> {
         match sep {
+            '.' if iter.peek() == Some(&'.') => {
+                iter.next();
+                Ok(AliceToken::Sep(AliceSeparator::Range))
+            }
             '(' => Ok(AliceToken::Sep(AliceSeparator::OpenP)),
             ')' => Ok(AliceToken::Sep(AliceSeparator::CloseP)),
             '{' => Ok(AliceToken::Sep(AliceSeparator::OpenB)),
@@ -279,7 +571,7 @@ impl AliceLexer {
     fn gobble_ident_or_keyw(
         &self,
         start: char,
-        iter: &mut Peekable<Chars>,
+        iter: &mut SrcChars,
     ) -> Result<AliceToken, AliceLexerErr> {
         let mut s: String = start.into();
         while let Some(c) = iter.peek() {
@@ -294,6 +586,40 @@ impl AliceLexer {
     }
 }
 
+/// the documentation in the text of a doc comment without its leading `/**`: each line is
+/// trimmed, including a leading `*` as in
+/// ```text
+/// /**
+///  * adds one
+///  */
+/// ```
+fn doc_text(text: &str) -> String {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            line.strip_prefix('*').map(str::trim_start).unwrap_or(line)
+        })
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+/// true if the next two chars are `..`, which ends a number literal
+fn at_range(iter: &SrcChars) -> bool {
+    let mut ahead = iter.clone();
+    ahead.next() == Some('.') && ahead.peek() == Some(&'.')
+}
+
+/// name of a number base for error messages
+fn base_name(base: u32) -> &'static str {
+    match base {
+        16 => "hex",
+        8 => "octal",
+        2 => "binary",
+        _ => "decimal",
+    }
+}
+
 fn is_token_separator(c: &char) -> bool {
     AliceSeparator::contains(c) || AliceOp::contains(c) || c == &'\'' || c == &'"'
 }
@@ -341,3 +667,220 @@ impl From<char> for AliceOp {
 fn to_number_format_error<T: ToString>(e: T, loc: Loc) -> AliceLexerErr {
     AliceLexerErr::NumberFormatErr(e.to_string(), loc)
 }
+
+// on_add_token
+impl std::fmt::Display for AliceToken {
+    /// writes the token as it could appear in source code
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AliceToken::IdentOrKeyw(s) => write!(f, "{s}"),
+            AliceToken::String(s) => {
+                write!(f, "\"")?;
+                for c in s.chars() {
+                    write_escaped(f, c, '"')?;
+                }
+                write!(f, "\"")
+            }
+            AliceToken::Char(c) => {
+                write!(f, "'")?;
+                write_escaped(f, *c, '\'')?;
+                write!(f, "'")
+            }
+            AliceToken::Doc(doc) => write!(f, "/** {doc} */"),
+            AliceToken::Comment(comment) => write!(f, "{comment}"),
+            AliceToken::Newline => writeln!(f),
+            AliceToken::Number(n, true) => write!(f, "{n:?}"),
+            AliceToken::Number(n, false) => write!(f, "{}", *n as i64),
+            AliceToken::Sep(sep) => write!(f, "{sep}"),
+            AliceToken::Op(op) => write!(f, "{op}"),
+        }
+    }
+}
+
+/// whether `tok` is written right after `prev` without a space, like `->`, `5@float`,
+/// `[1 2]`, `f()`, `.x` and `0..5`
+pub fn glued(prev: &AliceToken, tok: &AliceToken) -> bool {
+    use AliceSeparator::*;
+    matches!(
+        (prev, tok),
+        (AliceToken::Op(AliceOp::Sub), AliceToken::Op(AliceOp::Gt))
+            | (
+                AliceToken::Op(AliceOp::Gt | AliceOp::Lt | AliceOp::Eqs | AliceOp::Bang),
+                AliceToken::Op(AliceOp::Eqs)
+            )
+            | (
+                AliceToken::Sep(At | OpenS | Period | Range),
+                AliceToken::IdentOrKeyw(_)
+                    | AliceToken::Number(..)
+                    | AliceToken::String(_)
+                    | AliceToken::Char(_)
+                    | AliceToken::Sep(OpenS)
+            )
+            | (
+                AliceToken::IdentOrKeyw(_)
+                    | AliceToken::Number(..)
+                    | AliceToken::String(_)
+                    | AliceToken::Char(_)
+                    | AliceToken::Sep(CloseS | CloseP),
+                AliceToken::Sep(At | CloseS | Range)
+            )
+            | (AliceToken::Op(_), AliceToken::Sep(CloseS))
+            | (AliceToken::IdentOrKeyw(_), AliceToken::Sep(OpenP))
+            | (AliceToken::Sep(OpenP), AliceToken::Sep(CloseP))
+            | (_, AliceToken::Sep(Colon | Comma))
+    )
+}
+
+/// writes a char of a string or char literal delimited by `quote`
+fn write_escaped(f: &mut std::fmt::Formatter, c: char, quote: char) -> std::fmt::Result {
+    match c {
+        '\\' => write!(f, "\\\\"),
+        '\n' => write!(f, "\\n"),
+        '\r' => write!(f, "\\r"),
+        '\t' => write!(f, "\\t"),
+        c if c == quote => write!(f, "\\{c}"),
+        c => write!(f, "{c}"),
+    }
+}
+
+// on_add_sep
+impl std::fmt::Display for AliceSeparator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let c = match self {
+            AliceSeparator::OpenP => "(",
+            AliceSeparator::CloseP => ")",
+            AliceSeparator::OpenB => "{",
+            AliceSeparator::CloseB => "}",
+            AliceSeparator::OpenS => "[",
+            AliceSeparator::CloseS => "]",
+            AliceSeparator::Comma => ",",
+            AliceSeparator::Period => ".",
+            AliceSeparator::Range => "..",
+            AliceSeparator::Colon => ":",
+            AliceSeparator::Semi => ";",
+            AliceSeparator::At => "@",
+        };
+        write!(f, "{c}")
+    }
+}
+
+// on_add_op
+impl std::fmt::Display for AliceOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match self {
+            AliceOp::Add => "+",
+            AliceOp::Sub => "-",
+            AliceOp::Mul => "*",
+            AliceOp::Div => "/",
+            AliceOp::Pow => "**",
+            AliceOp::Mod => "%",
+            AliceOp::Eqs => "=",
+            AliceOp::Gt => ">",
+            AliceOp::Lt => "<",
+            AliceOp::Bang => "!",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(src: &str) -> Vec<AliceToken> {
+        AliceLexer::new(src.into(), "test".into())
+            .tokenize()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.token)
+            .collect()
+    }
+
+    /// the message of the error tokenizing the source fails with
+    fn lex_err(src: &str) -> String {
+        match AliceLexer::new(src.into(), "test".into()).tokenize() {
+            Ok(_) => panic!("tokenizing succeeded: {src}"),
+            Err(e) => crate::error::AliceError::from(e).to_string(),
+        }
+    }
+
+    #[test]
+    fn letters_after_numbers_are_explained() {
+        assert!(lex_err("5int").contains("conversions are written like 5@int"));
+        assert!(lex_err("5.0f").contains("numbers must be followed by whitespace or a separator"));
+        assert!(matches!(
+            tokens("5@int")[..],
+            [
+                AliceToken::Number(_, false),
+                AliceToken::Sep(AliceSeparator::At),
+                AliceToken::IdentOrKeyw(_)
+            ]
+        ));
+    }
+
+    #[test]
+    fn negative_literals_in_every_base() {
+        assert!(matches!(tokens("-0xFF")[..], [AliceToken::Number(n, false)] if n == -255.0));
+        assert!(matches!(tokens("-0b10")[..], [AliceToken::Number(n, false)] if n == -2.0));
+        assert!(matches!(tokens("-1e3")[..], [AliceToken::Number(n, true)] if n == -1000.0));
+    }
+
+    #[test]
+    fn char_escapes() {
+        assert!(matches!(
+            tokens(r"'\n' '\t' '\u{41}' '\x41'")[..],
+            [
+                AliceToken::Char('\n'),
+                AliceToken::Char('\t'),
+                AliceToken::Char('A'),
+                AliceToken::Char('A')
+            ]
+        ));
+        assert!(lex_err("'ab'").contains("must contain exactly one character"));
+    }
+
+    #[test]
+    fn block_comments_span_lines() {
+        let tokens = AliceLexer::new("1 println\n/* a\n b */\n2 println".into(), "test".into())
+            .tokenize()
+            .unwrap();
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[2].loc.line, 4);
+        assert!(lex_err("1 println\n  /* open")
+            .contains("test:2:3: error tokenizing: unterminated block comment"));
+    }
+
+    #[test]
+    fn leading_zeros_are_decimal() {
+        assert!(matches!(tokens("007")[..], [AliceToken::Number(n, false)] if n == 7.0));
+        assert!(matches!(tokens("0")[..], [AliceToken::Number(n, false)] if n == 0.0));
+        assert!(matches!(tokens("0.5")[..], [AliceToken::Number(n, true)] if n == 0.5));
+    }
+
+    #[test]
+    fn digit_separators_in_exponents() {
+        assert!(matches!(tokens("1e1_0")[..], [AliceToken::Number(n, true)] if n == 1e10));
+        assert!(lex_err("1e_1").contains("missing digits in the exponent"));
+        assert!(lex_err("1e10_").contains("misplaced digit separator '_' in '1e10_'"));
+    }
+
+    #[test]
+    fn ranges_are_one_token() {
+        assert!(matches!(
+            tokens("0..5")[..],
+            [
+                AliceToken::Number(a, false),
+                AliceToken::Sep(AliceSeparator::Range),
+                AliceToken::Number(b, false)
+            ] if a == 0.0 && b == 5.0
+        ));
+        assert!(matches!(
+            tokens("x .y")[..],
+            [
+                AliceToken::IdentOrKeyw(_),
+                AliceToken::Sep(AliceSeparator::Period),
+                AliceToken::IdentOrKeyw(_)
+            ]
+        ));
+    }
+}
@@ -1,5 +1,9 @@
 #![allow(dead_code)]
+mod error;
 mod flow;
+mod format;
+mod interactive;
+mod json;
 mod keyword;
 mod lexer;
 mod loc;
@@ -10,6 +14,7 @@ mod statement;
 mod type_check;
 mod utils;
 
+use crate::error::{AliceError, Stage};
 use crate::lexer::AliceLexer;
 use crate::parser::AliceParser;
 use clap::Parser;
@@ -17,13 +22,40 @@ use std::time::{Duration, Instant};
 
 fn main() -> Result<(), String> {
     let args = AliceArgs::parse();
+    let mut stack = crate::runtime::AliceStack::new(args.stack_capacity);
+    let mut table = crate::runtime::AliceTable::new(args.table_capacity);
+    let mut ctx = crate::runtime::AliceContext::new(args.args);
+    ctx.io_allowed = !args.no_io;
+    ctx.max_call_depth = args.max_call_depth;
+    if args.profile {
+        ctx.profile = Some(crate::runtime::Profile::default());
+    }
+    if args.trace {
+        stack.enable_trace();
+    }
+    if let Some(seed) = args.seed {
+        ctx.rng = crate::runtime::AliceRng::new(seed);
+    }
     if args.path.is_none() {
-        launch_interactive();
+        let mut interactive = crate::interactive::Interactive::new(stack, table, ctx);
+        interactive.pow_overflow_to_float = args.pow_overflow_float;
+        interactive.type_stack.strict = args.strict;
+        interactive.run();
+        if let Some(profile) = &interactive.ctx.profile {
+            print!("{}", profile.summary());
+        }
+        return Ok(());
     }
 
     let bench = args.bench.unwrap_or(false);
     let mut total = Duration::from_millis(0);
     let file = args.path.unwrap(); // unwrapping here is safe due to previous check
+    if args.fmt {
+        let formatted = crate::format::format(load_src(&file)?, file)
+            .map_err(|e| AliceError::from(e).to_string())?;
+        print!("{formatted}");
+        return Ok(());
+    }
     let t0 = Instant::now();
     let tokens = AliceLexer::new(load_src(&file)?, file.clone()).tokenize();
     if bench {
@@ -35,10 +67,11 @@ fn main() -> Result<(), String> {
         );
     }
     if let Ok(tokens) = tokens {
-        let mut stack = crate::runtime::AliceStack::new(64);
-        let mut table = crate::runtime::AliceTable::new(32);
         let t0 = Instant::now();
-        let statements = AliceParser::new(tokens).parse(None);
+        let statements = AliceParser::new(tokens)
+            .pow_overflow_to_float(args.pow_overflow_float)
+            .strict(args.strict)
+            .parse(None);
         if bench {
             let elapsed = t0.elapsed();
             total += elapsed;
@@ -47,15 +80,16 @@ fn main() -> Result<(), String> {
                 display_duration(&elapsed)
             );
         }
-        if let Err(msg) = statements {
-            return Err(format!("Error parsing {file}: {msg}"));
+        if let Err(e) = statements {
+            return Err(e.to_string());
         }
 
         let statements = statements.unwrap();
         let t0 = Instant::now();
-        for s in statements {
-            if let Err(e) = s.execute(&mut stack, &mut table) {
-                return Err(format!("Error executing {file}: {e}"));
+        for (i, s) in statements.iter().enumerate() {
+            stack.origin.statement = i + 1;
+            if let Err(e) = s.run(&mut stack, &mut table, &mut ctx) {
+                return Err(AliceError::in_file(Stage::Executing, e, file).to_string());
             }
         }
         if bench {
@@ -64,16 +98,21 @@ fn main() -> Result<(), String> {
             println!("[bench] executing program:\t{}", display_duration(&elapsed));
             println!("[bench] total elapsed:\t\t{}", display_duration(&total));
         }
+        if let Some(profile) = &ctx.profile {
+            print!("{}", profile.summary());
+        }
     } else {
-        return Err(format!("Error tokenizing {file} {:?}: ", tokens.err()));
+        // unwrapping safe due to the if let above
+        return Err(AliceError::from(tokens.unwrap_err()).to_string());
     }
-    return Ok(());
+    Ok(())
 }
 
 #[derive(Parser, Debug)]
 #[clap(author = "Malte Dostal <malted@duck.com>")]
 #[clap(name = "alicelang")]
 #[clap(about = "alicelang cli")]
+#[clap(trailing_var_arg = true)]
 struct AliceArgs {
     #[clap(short, long, value_parser)]
     /// Enable emitting of intermediate representation.
@@ -82,52 +121,43 @@ struct AliceArgs {
     #[clap(short, long)]
     /// enables benchmark output
     bench: Option<bool>,
+    #[clap(long)]
+    /// disables file and environment access for the alice program
+    no_io: bool,
+    #[clap(long, value_parser)]
+    /// seed for the random number generator, for reproducible runs
+    seed: Option<u64>,
+    #[clap(long)]
+    /// prints how often each kind of statement executed and how long it took
+    profile: bool,
+    #[clap(long)]
+    /// remembers which statement pushed each stack value, shown by pstack and in errors
+    trace: bool,
+    #[clap(long)]
+    /// prints the formatted source of the alice file instead of running it
+    fmt: bool,
+    #[clap(long)]
+    /// makes int ** int result in a float, which can't overflow, instead of an int
+    pow_overflow_float: bool,
+    #[clap(long)]
+    /// makes type check warnings, like unreachable code, errors
+    strict: bool,
+    #[clap(long, value_parser, default_value_t = crate::runtime::DEFAULT_STACK_CAPACITY)]
+    /// initial capacity of the stack; it grows beyond that as needed
+    stack_capacity: usize,
+    #[clap(long, value_parser, default_value_t = crate::runtime::DEFAULT_TABLE_CAPACITY)]
+    /// initial capacity of the variable table; it grows beyond that as needed
+    table_capacity: usize,
+    #[clap(long, value_parser, default_value_t = crate::runtime::DEFAULT_MAX_CALL_DEPTH)]
+    /// how deeply function calls may nest before erroring
+    max_call_depth: usize,
     #[clap(value_parser)]
     /// Path to the alice file.
     /// Empty for interactive mode
     path: Option<String>,
-}
-
-fn launch_interactive() {
-    let mut stack = crate::runtime::AliceStack::new(64);
-    let mut type_stack = crate::type_check::TypeStack::new();
-    let mut table = crate::runtime::AliceTable::new(64);
-
-    use std::io::Write;
-    println!("interactive alice");
-    let mut input = String::new();
-    loop {
-        print!("alice>>");
-        std::io::stdout().flush().expect("flushing stdout failed");
-        std::io::stdin()
-            .read_line(&mut input)
-            .expect("reading stdin failed");
-        let s: String = input.trim().into();
-        input.clear();
-        let tokens = AliceLexer::new(s, "<interactive>".into()).tokenize();
-        if let Ok(tokens) = tokens {
-            // println!("{tokens:?}");
-            let parser = AliceParser::new(tokens);
-            let statements = parser.parse(Some(&mut type_stack));
-            if let Err(msg) = statements {
-                eprintln!("{}", format!("error parsing input: {msg}"));
-                // have to redo type stack
-                type_stack.vals.clear();
-                for val in &stack.stack {
-                    type_stack.vals.push(crate::type_check::type_bit(val));
-                }
-            } else {
-                let statements = statements.ok().unwrap();
-                for s in statements {
-                    if let Err(e) = s.execute(&mut stack, &mut table) {
-                        eprintln!("error: {e}");
-                    }
-                }
-            }
-        } else {
-            eprintln!("Error: {:?}", tokens.err().unwrap());
-        }
-    }
+    #[clap(value_parser, allow_hyphen_values = true)]
+    /// Arguments passed on to the alice program, including ones that look like flags
+    args: Vec<String>,
 }
 
 fn load_src(path: &String) -> Result<String, String> {
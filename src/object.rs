@@ -6,19 +6,141 @@ use std::collections::HashMap;
 use std::fmt;
 use std::rc::Rc;
 
+/// objects have value semantics: a clone, e. g. by dup or reading a variable, is
+/// independent of the original
 #[derive(Debug, Clone)]
 pub struct AliceObj {
     pub type_name: String,
-    /// hash of the type name and type signature, truncated to the 27 most significant bits
-    /// using type_check::OBJECT_SIG_MASK
+    /// hash of the type name and type signature, truncated to the 24 most significant bits
+    /// using type_check::OBJECT_SIG_MASK, see AliceObjType::new
     pub type_hash: u32,
-    pub members: HashMap<String, AliceVal>,
+    /// shared between clones, so that copying a large object is cheap. nothing mutates
+    /// members yet, a member setter would have to copy them first (Rc::make_mut)
+    pub members: Rc<HashMap<String, AliceVal>>,
     pub functions: HashMap<String, Vec<AliceFun>>,
 }
 
+/// an object type declared by `object Name { member: type ... }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliceObjType {
+    pub name: String,
+    /// the type bits of objects of this type, OBJECT plus the signature hash
+    pub type_hash: u32,
+    /// in declaration order, which is the order the constructor takes them from
+    /// the stack, bottom first
+    pub members: Vec<(String, u32)>,
+    /// values of the members declared with `= literal`, used if a construction by member
+    /// names leaves them out, see ConstructStatement. not part of the signature
+    pub defaults: HashMap<String, AliceVal>,
+}
+
+impl AliceObjType {
+    pub fn new(name: String, members: Vec<(String, u32)>) -> Self {
+        // FNV-1a, so that the hash stays the same across runs and builds
+        let mut hash: u32 = 0x811c9dc5;
+        let mut feed = |bytes: &[u8]| {
+            for b in bytes {
+                hash ^= *b as u32;
+                hash = hash.wrapping_mul(0x01000193);
+            }
+        };
+        feed(name.as_bytes());
+        for (member, ty) in &members {
+            feed(b" ");
+            feed(member.as_bytes());
+            feed(&ty.to_be_bytes());
+        }
+        Self {
+            name,
+            type_hash: (hash & OBJECT_SIG_MASK) | OBJECT,
+            members,
+            defaults: HashMap::new(),
+        }
+    }
+
+    /// the type of the given member, if objects of this type have it
+    pub fn member_type(&self, name: &str) -> Option<u32> {
+        self.members
+            .iter()
+            .find(|(member, _)| member == name)
+            .map(|(_, ty)| *ty)
+    }
+
+    /// the signature of the constructor: the member types, top of the stack first
+    pub fn args(&self) -> StackPattern {
+        StackPattern(self.members.iter().rev().map(|(_, ty)| *ty).collect())
+    }
+
+    /// an object of this type with the given members
+    pub fn instantiate(&self, members: HashMap<String, AliceVal>) -> AliceVal {
+        AliceVal::Object(Some(AliceObj {
+            type_name: self.name.clone(),
+            type_hash: self.type_hash,
+            members: Rc::new(members),
+            functions: HashMap::new(),
+        }))
+    }
+}
+
+/// name of the built-in object type of the pairs made by zip and enumerate
+pub const PAIR: &str = "Pair";
+pub const PAIR_FIRST: &str = "first";
+pub const PAIR_SECOND: &str = "second";
+
+/// the type of pairs of the given member types, `Pair { first: _ second: _ }`.
+/// like any object type, it is identified by its signature, so there is one per
+/// combination of member types
+pub fn pair_type(first: u32, second: u32) -> AliceObjType {
+    AliceObjType::new(
+        PAIR.into(),
+        vec![(PAIR_FIRST.into(), first), (PAIR_SECOND.into(), second)],
+    )
+}
+
+/// a pair of the given type, see pair_type
+pub fn pair(ty: &AliceObjType, first: AliceVal, second: AliceVal) -> AliceVal {
+    ty.instantiate(HashMap::from([
+        (PAIR_FIRST.into(), first),
+        (PAIR_SECOND.into(), second),
+    ]))
+}
+
+/// the constructor, popping the members and pushing the new object
+impl Statement for AliceObjType {
+    fn in_pattern(&self) -> StackPattern {
+        self.args()
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(self.type_hash)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let mut members = HashMap::with_capacity(self.members.len());
+        for (member, _) in self.members.iter().rev() {
+            members.insert(member.clone(), stack.pop()?);
+        }
+        stack.push(self.instantiate(members));
+        Ok(())
+    }
+}
+
+/// objects are equal if they are of the same type and their members are equal.
+/// functions aren't compared, they belong to the type
 impl PartialEq for AliceObj {
     fn eq(&self, other: &Self) -> bool {
-        self.type_hash == other.type_hash
+        self.type_hash == other.type_hash && self.members == other.members
+    }
+}
+
+impl AliceObj {
+    pub fn member(&self, name: &str) -> Option<&AliceVal> {
+        self.members.get(name)
     }
 }
 
@@ -29,6 +151,12 @@ pub struct AliceFun {
     /// 0 means no return type
     pub return_type: u32,
     pub body: Vec<Rc<dyn Statement>>,
+    /// the source code of the definition, if it was parsed from source
+    pub source: Option<String>,
+    /// if true, results are cached by argument values, see AliceTable::memo
+    pub memoized: bool,
+    /// the text of the doc comment right before the definition, if any
+    pub doc: Option<String>,
 }
 
 impl AliceFun {
@@ -37,27 +165,33 @@ impl AliceFun {
             args,
             return_type,
             body,
+            source: None,
+            memoized: false,
+            doc: None,
         }
     }
 
-    pub fn type_check(&self) -> Result<(), TypeCheckError> {
-        let mut stack = TypeStack::new();
+    /// checks the body against the signature. the body sees the variables and functions
+    /// bound in the scope of the definition
+    pub fn type_check(&self, scope: &TypeStack) -> Result<(), TypeCheckError> {
+        let mut stack = scope.scope();
         self.args.push(&mut stack);
-        check_rc(&mut stack, &self.body)
-            .map_err(|e| e.prefix("Function signature doesn't allow for this: ".into()))?;
-        if self.return_type == 0 && stack.vals.len() == 0 {
+        check_rc(&mut stack, &self.body)?;
+        if self.return_type == 0 && stack.vals.is_empty() {
             return Ok(());
         }
-        if stack.vals.len() == 0 {
-            Err(TypeCheckError(
+        if stack.vals.is_empty() {
+            Err(TypeCheckError::new(
                 "function should return something but doesn't".into(),
             ))
         } else if stack.vals.len() > 1 {
-            Err(TypeCheckError(
+            Err(TypeCheckError::new(
                 "function returns more than one value".into(),
             ))
         } else if stack.pop().unwrap() != self.return_type {
-            Err(TypeCheckError("function has wrong return type!".into()))
+            Err(TypeCheckError::new(
+                "function has wrong return type!".into(),
+            ))
         } else {
             Ok(())
         }
@@ -65,12 +199,20 @@ impl AliceFun {
 }
 
 impl Statement for AliceFun {
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         // todo! create new stack frame on table
-        for s in &self.body {
-            s.execute(stack, _table)?;
+        if ctx.call_depth >= ctx.max_call_depth {
+            return Err("maximum recursion depth exceeded".into());
         }
-        Ok(())
+        ctx.call_depth += 1;
+        let result = self.body.iter().try_for_each(|s| s.run(stack, table, ctx));
+        ctx.call_depth -= 1;
+        result
     }
 }
 
@@ -90,3 +232,25 @@ impl fmt::Debug for AliceFun {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: i64) -> AliceObj {
+        let ty = AliceObjType::new("Point".into(), vec![("x".into(), INT)]);
+        match ty.instantiate(HashMap::from([("x".into(), AliceVal::Int(Some(x)))])) {
+            AliceVal::Object(Some(o)) => o,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn clones_share_members() {
+        let original = point(1);
+        let copy = original.clone();
+        assert!(Rc::ptr_eq(&original.members, &copy.members));
+        assert_eq!(original, copy);
+        assert_ne!(original, point(2));
+    }
+}
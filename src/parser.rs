@@ -1,21 +1,28 @@
+use crate::error::{AliceError, Stage};
 use crate::flow::*;
-use crate::keyword::{keywords, Keyword};
-use crate::lexer::{AliceOp, AliceSeparator, AliceToken};
+use crate::keyword::{keywords, Keyword, KW_ALIAS};
+use crate::lexer::{glued, AliceOp, AliceSeparator, AliceToken, LocatedToken};
+use crate::loc::Loc;
 use crate::object::*;
 use crate::runtime::AliceVal;
 use crate::statement::*;
 use crate::type_check::*;
 use crate::utils::*;
 
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::iter::Peekable;
+use std::rc::Rc;
 use std::slice::Iter;
 
+// when adding a new word, must modify all places comment-marked:
+// on_add_word
 pub const ST_PRINTLN: &str = "println";
 pub const ST_PRINT: &str = "print";
 pub const ST_PRINT_STACK: &str = "pstack";
 pub const ST_EXIT: &str = "exit";
 pub const ST_OK_EXIT: &str = "okexit";
+pub const ST_ABORT: &str = "abort";
 pub const ST_DROP: &str = "drop";
 pub const ST_SWAP: &str = "swap";
 pub const ST_DUP: &str = "dup";
@@ -23,37 +30,248 @@ pub const ST_OVER: &str = "over";
 pub const ST_ROT: &str = "rot";
 pub const ST_CLEAR: &str = "clear";
 pub const ST_READ_LINE: &str = "readln";
+pub const ST_ARGS: &str = "args";
+pub const ST_READ_FILE: &str = "readfile";
+pub const ST_WRITE_FILE: &str = "writefile";
+pub const ST_RAND: &str = "rand";
+pub const ST_NOW: &str = "now";
+pub const ST_SLEEP: &str = "sleep";
+pub const ST_TO_INT: &str = "toint";
+pub const ST_DUMP_JSON: &str = "dumpjson";
+pub const ST_LEN: &str = "len";
+pub const ST_BYTE_LEN: &str = "bytelen";
+pub const ST_TO_JSON: &str = "tojson";
+pub const ST_FROM_JSON: &str = "fromjson";
+pub const ST_CLAMP: &str = "clamp";
+pub const ST_INT_MAX: &str = "intmax";
+pub const ST_INT_MIN: &str = "intmin";
+pub const ST_FLOAT_MAX: &str = "floatmax";
+pub const ST_FLOAT_INF: &str = "floatinf";
+pub const ST_FLOAT_NAN: &str = "floatnan";
+pub const ST_FIELDS: &str = "fields";
+pub const ST_REVERSE: &str = "reverse";
+pub const ST_CONTAINS: &str = "contains";
+pub const ST_INDEX_OF: &str = "indexof";
+pub const ST_REPLACE: &str = "replace";
+pub const ST_ROTN: &str = "rotn";
+pub const ST_TO_FIXED: &str = "tofixed";
+pub const ST_TO_SCIENTIFIC: &str = "toscientific";
+pub const ST_ZIP: &str = "zip";
+pub const ST_ENUMERATE: &str = "enumerate";
+pub const ST_COUNT: &str = "count";
+pub const ST_ASSERT_STACK: &str = "assertstack";
+pub const ST_IS_EVEN: &str = "iseven";
+pub const ST_IS_ODD: &str = "isodd";
+pub const ST_IS_POSITIVE: &str = "ispositive";
+pub const ST_IS_NEGATIVE: &str = "isnegative";
+pub const ST_IS_ZERO: &str = "iszero";
+pub const ST_SLICE: &str = "slice";
+
+/// all built-in words, for suggestions on typos
+/// on_add_word
+pub const WORDS: &[&str] = &[
+    ST_PRINTLN,
+    ST_PRINT,
+    ST_PRINT_STACK,
+    ST_EXIT,
+    ST_OK_EXIT,
+    ST_ABORT,
+    ST_DROP,
+    ST_SWAP,
+    ST_DUP,
+    ST_OVER,
+    ST_ROT,
+    ST_CLEAR,
+    ST_READ_LINE,
+    ST_ARGS,
+    ST_READ_FILE,
+    ST_WRITE_FILE,
+    ST_RAND,
+    ST_NOW,
+    ST_SLEEP,
+    ST_TO_INT,
+    ST_DUMP_JSON,
+    ST_LEN,
+    ST_BYTE_LEN,
+    ST_TO_JSON,
+    ST_FROM_JSON,
+    ST_CLAMP,
+    ST_INT_MAX,
+    ST_INT_MIN,
+    ST_FLOAT_MAX,
+    ST_FLOAT_INF,
+    ST_FLOAT_NAN,
+    ST_FIELDS,
+    ST_REVERSE,
+    ST_CONTAINS,
+    ST_INDEX_OF,
+    ST_REPLACE,
+    ST_ROTN,
+    ST_TO_FIXED,
+    ST_TO_SCIENTIFIC,
+    ST_ZIP,
+    ST_ENUMERATE,
+    ST_COUNT,
+    ST_ASSERT_STACK,
+    ST_IS_EVEN,
+    ST_IS_ODD,
+    ST_IS_POSITIVE,
+    ST_IS_NEGATIVE,
+    ST_IS_ZERO,
+    ST_SLICE,
+];
+
+/// reserved for discarding values, e. g. the default arm of match;
+/// identifiers may contain underscores but not be just this
+pub const DISCARD: &str = "_";
+
+/// how deep blocks and lists may be nested by default, see AliceParser::max_depth
+pub const DEFAULT_MAX_DEPTH: usize = 256;
 
 type TokenIter<'a> = Peekable<Iter<'a, AliceToken>>;
 
 pub struct AliceParser {
     tokens: Vec<AliceToken>,
+    /// the location of each token in self.tokens
+    locs: Vec<Loc>,
     keywords: HashMap<String, Keyword>,
+    /// see PowStatement::overflow_to_float
+    pow_overflow_to_float: bool,
+    /// see AliceParser::strict
+    strict: bool,
+    /// words defined by `alias`, mapped to the token they stand for
+    aliases: RefCell<HashMap<String, AliceToken>>,
+    /// the type bits of the object types declared so far by name, to be used as member types
+    object_types: RefCell<HashMap<String, u32>>,
+    /// see AliceParser::max_depth
+    max_depth: usize,
+    /// how deep the block or list currently being parsed is nested
+    depth: Cell<usize>,
+    /// see AliceParser::max_statements
+    max_statements: usize,
+    /// how many statements have been parsed so far
+    statements: Cell<usize>,
 }
 
 impl AliceParser {
-    pub fn new(tokens: Vec<AliceToken>) -> Self {
+    pub fn new(tokens: Vec<LocatedToken>) -> Self {
+        let (tokens, locs) = tokens.into_iter().map(|t| (t.token, t.loc)).unzip();
         Self {
             tokens,
+            locs,
             keywords: keywords(),
+            pow_overflow_to_float: false,
+            strict: false,
+            aliases: RefCell::new(HashMap::new()),
+            object_types: RefCell::new(HashMap::new()),
+            max_depth: DEFAULT_MAX_DEPTH,
+            depth: Cell::new(0),
+            max_statements: usize::MAX,
+            statements: Cell::new(0),
         }
     }
 
-    /// prev = Some(_) assumed interactive mode
-    pub fn parse(&self, prev: Option<&mut TypeStack>) -> Result<Vec<Box<dyn Statement>>, String> {
-        let mut statements = Vec::new();
+    /// limits how deep blocks and lists may be nested, so that untrusted input can't
+    /// overflow the stack of the recursive parser
+    pub fn max_depth(mut self, max: usize) -> Self {
+        self.max_depth = max;
+        self
+    }
+
+    /// limits how many statements the program may consist of, counting those in blocks
+    pub fn max_statements(mut self, max: usize) -> Self {
+        self.max_statements = max;
+        self
+    }
+
+    /// makes int ** int result in a float instead of erroring on overflow
+    pub fn pow_overflow_to_float(mut self, on: bool) -> Self {
+        self.pow_overflow_to_float = on;
+        self
+    }
+
+    /// starts with the aliases defined by an earlier parser, see AliceParser::aliases
+    pub fn with_aliases(self, aliases: HashMap<String, AliceToken>) -> Self {
+        self.aliases.replace(aliases);
+        self
+    }
 
+    /// the aliases defined so far, for parsing more input of the same program later,
+    /// like the next line in the REPL
+    pub fn aliases(&self) -> HashMap<String, AliceToken> {
+        self.aliases.borrow().clone()
+    }
+
+    /// starts with the object types declared by an earlier parser, see AliceParser::aliases
+    pub fn with_object_types(self, object_types: HashMap<String, u32>) -> Self {
+        self.object_types.replace(object_types);
+        self
+    }
+
+    /// the object types declared so far, like AliceParser::aliases
+    pub fn object_types(&self) -> HashMap<String, u32> {
+        self.object_types.borrow().clone()
+    }
+
+    /// makes type check warnings, like unreachable code, errors
+    pub fn strict(mut self, on: bool) -> Self {
+        self.strict = on;
+        self
+    }
+
+    /// prev = Some(_) assumed interactive mode.
+    /// syntax errors are located at the token they were found at, type errors only in the
+    /// file unless the statement that found them knows its location
+    pub fn parse(
+        &self,
+        prev: Option<&mut TypeStack>,
+    ) -> Result<Vec<Box<dyn Statement>>, AliceError> {
         let mut iter = self.tokens.iter().peekable();
-        while let Some(token) = iter.next() {
-            statements.push(self.gobble_token(token, &mut iter)?);
-        }
-        if let Some(stack) = prev {
-            check_interactive(stack, &statements)?;
-            Ok(statements)
+        let mut statements = Vec::new();
+        // the location of the first token of each top level statement
+        let mut locs = Vec::new();
+        self.gobble_statements_until(
+            &mut iter,
+            None,
+            |msg, iter| self.error_at(msg, iter),
+            |s, loc| {
+                statements.push(s);
+                locs.push(loc.clone());
+                Ok(())
+            },
+        )?;
+        let file = self
+            .locs
+            .first()
+            .map(|loc| loc.file.clone())
+            .unwrap_or_default();
+        let checked = if let Some(stack) = prev {
+            check_interactive(stack, &statements).map(|_| stack.warnings.take())
         } else {
-            check(&statements)?;
-            Ok(statements)
+            check(&statements, &locs, self.strict)
+        };
+        let warnings = checked.map_err(|e| e.in_file(file.clone()))?;
+        for warning in warnings {
+            eprintln!("{file}: warning: {warning}");
         }
+        Ok(statements)
+    }
+
+    /// parses top level statements one at a time without type checking them, handing each
+    /// to `f` before parsing on, so that the statements before a syntax error still take
+    /// effect. used by the REPL. syntax errors are turned into `E` by `on_error`
+    pub fn parse_each<E>(
+        &self,
+        on_error: impl Fn(AliceError) -> E,
+        mut f: impl FnMut(Box<dyn Statement>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let mut iter = self.tokens.iter().peekable();
+        self.gobble_statements_until(
+            &mut iter,
+            None,
+            |msg, iter| on_error(self.error_at(msg, iter)),
+            |s, _| f(s),
+        )
     }
 
     fn gobble_token(
@@ -64,9 +282,16 @@ impl AliceParser {
         match token {
             AliceToken::IdentOrKeyw(iok) => self.gobble_ident_or_kw(iok, iter),
             AliceToken::String(s) => self.gobble_string_literal(s, iter),
+            AliceToken::Char(c) => self.gobble_char_literal(*c, iter),
             AliceToken::Number(f, dec) => self.gobble_number_literal(*f, *dec, iter),
             AliceToken::Op(op) => self.gobble_operator(op, iter),
-            _ => todo!(),
+            AliceToken::Sep(AliceSeparator::OpenS) => self.gobble_list(iter),
+            AliceToken::Sep(AliceSeparator::Period) => self.gobble_member_access(iter),
+            AliceToken::Sep(sep) => Err(format!("unexpected separator '{sep}'")),
+            AliceToken::Doc(_) => Err("unexpected doc comment".into()),
+            AliceToken::Comment(_) | AliceToken::Newline => {
+                Err("comments and line breaks are only lexed for formatting".into())
+            }
         }
     }
 
@@ -77,6 +302,16 @@ impl AliceParser {
     ) -> Result<Box<dyn Statement>, String> {
         if let Some(kw) = self.keywords.get(iok) {
             self.gobble_kw(kw, iter)
+        } else if let Some(target) = self.alias_target(iok) {
+            self.gobble_token(&target, iter)
+        } else if iok == ST_FROM_JSON {
+            self.gobble_from_json(iter)
+        } else if iok == DISCARD {
+            Err(format!("{DISCARD} is reserved and can't be used as a word"))
+        } else if iok == ST_ROTN {
+            Err(format!(
+                "{ST_ROTN} needs the number of values as a literal right before it, e. g. 4 {ST_ROTN}"
+            ))
         } else {
             if let Some(statement) = self.maybe_gobble_statement(iok) {
                 Ok(statement)
@@ -91,12 +326,84 @@ impl AliceParser {
             Keyword::True => PushStatement(AliceVal::Bool(Some(true))),
             Keyword::False => PushStatement(AliceVal::Bool(Some(false))),
             Keyword::Let => return self.gobble_let(iter),
-            Keyword::Fun => return self.gobble_fun(iter),
+            Keyword::Fun => return self.gobble_fun(iter, false),
+            Keyword::Memoize => return self.gobble_memoize(iter),
             Keyword::If => return self.gobble_if(iter),
-            _ => todo!(),
+            Keyword::Try => return self.gobble_try(iter),
+            Keyword::While => return self.gobble_while(iter),
+            Keyword::For => return self.gobble_for(iter),
+            Keyword::Match => return self.gobble_match(iter),
+            Keyword::Object => return self.gobble_object(iter),
+            Keyword::Not => return Ok(Box::new(NotStatement)),
+            Keyword::And => return Ok(Box::new(AndStatement)),
+            Keyword::Or => return Ok(Box::new(OrStatement)),
+            Keyword::Else => return Err("else without preceding if".into()),
+            Keyword::Catch => return Err("catch without preceding try".into()),
+            Keyword::Alias => {
+                return Err("alias doesn't push anything; it can't be aliased or converted".into())
+            }
         }))
     }
 
+    /// syntax:
+    /// fromjson = "fromjson", "@", type
+    /// where type can also be the name of an object type
+    fn gobble_from_json(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
+        let mut lookahead = iter.clone();
+        if let (Some(AliceToken::Sep(AliceSeparator::At)), Some(AliceToken::IdentOrKeyw(name))) =
+            (lookahead.next(), lookahead.next())
+        {
+            if AliceVal::for_type_name(name).is_err() && !self.keywords.contains_key(name) {
+                iter.next();
+                iter.next();
+                return Ok(Box::new(FromJsonObjectStatement(name.clone())));
+            }
+        }
+        match self.maybe_at_conversion(iter)? {
+            Some(target) => Ok(Box::new(FromJsonStatement(target))),
+            None => Err(format!(
+                "{ST_FROM_JSON} needs the type of the value, e. g. {ST_FROM_JSON}@[int]"
+            )),
+        }
+    }
+
+    /// defines a new word for a built-in word or operator for the rest of the program.
+    /// syntax:
+    /// alias = "alias", ident, (word | operator)
+    fn gobble_alias(&self, iter: &mut TokenIter) -> Result<(), String> {
+        let ident = match iter.next() {
+            Some(AliceToken::IdentOrKeyw(ident)) => ident,
+            _ => return Err("alias syntax: 'alias' ident (word | operator)".into()),
+        };
+        self.check_bindable(ident, "an alias")?;
+        let target = match iter.next() {
+            Some(tok @ AliceToken::Op(_)) => tok.clone(),
+            Some(tok @ AliceToken::IdentOrKeyw(word)) if WORDS.contains(&word.as_str()) => {
+                tok.clone()
+            }
+            // aliasing an alias means aliasing what it stands for
+            Some(AliceToken::IdentOrKeyw(word)) if self.alias_target(word).is_some() => {
+                self.alias_target(word).unwrap()
+            }
+            Some(tok) => {
+                return Err(format!(
+                    "can only alias built-in words and operators, not {tok}"
+                ))
+            }
+            None => return Err(format!("missing word or operator to alias as {ident}")),
+        };
+        self.aliases.borrow_mut().insert(ident.clone(), target);
+        Ok(())
+    }
+
+    /// the token the given alias stands for
+    fn alias_target(&self, ident: &str) -> Option<AliceToken> {
+        self.aliases.borrow().get(ident).cloned()
+    }
+
+    /// the condition is the bool on top of the stack.
+    /// syntax:
+    /// if = "if", block, ["else", block]
     fn gobble_if(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
         if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::OpenB))) {
             return Err("if statements requires body block { ... }".into());
@@ -127,7 +434,235 @@ impl AliceParser {
     }
 
     /// syntax:
-    /// let = "let", ident, ":", type, ["=", literal]
+    /// try = "try", block, "catch", block
+    fn gobble_try(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
+        if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::OpenB))) {
+            return Err("try statements requires body block { ... }".into());
+        }
+        let body = self
+            .gobble_block(iter)?
+            .into_iter()
+            .map(|b| box_to_rc(b))
+            .collect();
+        if !matches!(iter.next(), Some(AliceToken::IdentOrKeyw(iok)) if iok == crate::keyword::KW_CATCH)
+        {
+            return Err("try body must be followed by catch { ... }".into());
+        }
+        if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::OpenB))) {
+            return Err("catch statements requires body block { ... }".into());
+        }
+        let handler = self
+            .gobble_block(iter)?
+            .into_iter()
+            .map(|b| box_to_rc(b))
+            .collect();
+        Ok(Box::new(TryCatchStatement(TryCatchContainer {
+            body,
+            handler,
+        })))
+    }
+
+    /// syntax:
+    /// while = "while", block, block
+    /// where the first block is the condition
+    fn gobble_while(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
+        if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::OpenB))) {
+            return Err("while statements requires condition block { ... }".into());
+        }
+        let cond = self
+            .gobble_block(iter)?
+            .into_iter()
+            .map(|b| box_to_rc(b))
+            .collect();
+        if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::OpenB))) {
+            return Err("while condition must be followed by body block { ... }".into());
+        }
+        let body = self
+            .gobble_block(iter)?
+            .into_iter()
+            .map(|b| box_to_rc(b))
+            .collect();
+        Ok(Box::new(WhileStatement(WhileContainer { cond, body })))
+    }
+
+    /// declares an object type along with its constructor `Name()`, which takes the
+    /// members from the stack, the first one deepest.
+    /// syntax:
+    /// object = "object", ident, "{", { ident, ":", type }, "}"
+    fn gobble_object(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
+        let name = match iter.next() {
+            Some(AliceToken::IdentOrKeyw(name)) => name,
+            _ => {
+                return Err(
+                    "object syntax: 'object' ident { member: type ['=' literal] ... }".into(),
+                )
+            }
+        };
+        self.check_bindable(name, "an object type")?;
+        if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::OpenB))) {
+            return Err(format!("expected '{{' after 'object {name}'"));
+        }
+        let mut members: Vec<(String, u32)> = Vec::new();
+        let mut defaults = HashMap::new();
+        loop {
+            let member = match iter.next() {
+                Some(AliceToken::Sep(AliceSeparator::CloseB)) => break,
+                Some(AliceToken::IdentOrKeyw(member)) => member,
+                Some(tok) => {
+                    return Err(format!(
+                        "expected member name or '}}' in object {name}, found '{tok}'"
+                    ))
+                }
+                None => return Err(format!("hit EOF while parsing object {name}")),
+            };
+            self.check_bindable(member, "a member")?;
+            if members.iter().any(|(m, _)| m == member) {
+                return Err(format!("object {name} declares member {member} twice"));
+            }
+            if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::Colon))) {
+                return Err(format!(
+                    "expected ': type' after member {member} of object {name}"
+                ));
+            }
+            let ty = self
+                .gobble_member_type(iter)
+                .map_err(|e| format!("member {member} of object {name}: {e}"))?;
+            members.push((member.clone(), ty));
+            if let Some(AliceToken::Op(AliceOp::Eqs)) = iter.peek() {
+                iter.next();
+                let default = match iter.next() {
+                    Some(tok) => self.gobble_literal(tok, iter)?,
+                    None => None,
+                };
+                match default {
+                    Some(default) => defaults.insert(member.clone(), default),
+                    None => {
+                        return Err(format!(
+                            "expected a literal as the default of member {member} in object {name}"
+                        ))
+                    }
+                };
+            }
+        }
+        let ty = AliceObjType {
+            defaults,
+            ..AliceObjType::new(name.clone(), members)
+        };
+        self.object_types
+            .borrow_mut()
+            .insert(name.clone(), ty.type_hash);
+        Ok(Box::new(ObjectStatement(Rc::new(ty))))
+    }
+
+    /// the type of an object member: a type name, a list type or the name of an object
+    /// type declared before
+    fn gobble_member_type(&self, iter: &mut TokenIter) -> Result<u32, String> {
+        if let Some(AliceToken::IdentOrKeyw(iok)) = iter.peek() {
+            if let Some(ty) = self.object_types.borrow().get(iok) {
+                iter.next();
+                return Ok(*ty);
+            }
+        }
+        self.gobble_type(iter).map(|val| type_bit(&val))
+    }
+
+    /// matches the value on top of the stack.
+    /// syntax:
+    /// match = "match", "{", { arm }, "}"
+    /// arm = (literal | ident | "_"), block
+    /// where an ident names an object type, whose members are bound in the arm,
+    /// and `_` is the default arm
+    fn gobble_match(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
+        if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::OpenB))) {
+            return Err("match statements requires arms in a block { ... }".into());
+        }
+        let mut arms = Vec::new();
+        let mut default = None;
+        loop {
+            let val = match iter.next() {
+                Some(AliceToken::Sep(AliceSeparator::CloseB)) => break,
+                Some(AliceToken::IdentOrKeyw(iok)) if iok == DISCARD => None,
+                Some(tok) => match (self.gobble_literal(tok, iter)?, tok) {
+                    (Some(val), _) => Some(MatchPattern::Literal(val)),
+                    (None, AliceToken::IdentOrKeyw(iok)) if !self.keywords.contains_key(iok) => {
+                        Some(MatchPattern::Object(iok.clone()))
+                    }
+                    (None, _) => {
+                        return Err(format!(
+                            "match arms start with a literal, an object type or _, found '{tok}'"
+                        ))
+                    }
+                },
+                None => return Err("missing delimiter: hit EOF while searching for '}'".into()),
+            };
+            if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::OpenB))) {
+                return Err("match arms requires body block { ... }".into());
+            }
+            let body = self
+                .gobble_block(iter)?
+                .into_iter()
+                .map(|b| box_to_rc(b))
+                .collect();
+            match val {
+                Some(val) => arms.push((val, body)),
+                None if default.is_some() => return Err("match has more than one _ arm".into()),
+                None => default = Some(body),
+            }
+        }
+        Ok(Box::new(MatchStatement(MatchContainer { arms, default })))
+    }
+
+    /// syntax:
+    /// for = "for", ident, "in", bound, "..", bound, block
+    /// where each bound is a single word or literal pushing an int, e. g. `for i in 0..n`
+    fn gobble_for(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
+        let syntax = "for syntax: 'for' ident 'in' start '..' end { ... }";
+        let var = match iter.next() {
+            Some(AliceToken::IdentOrKeyw(var)) => var.clone(),
+            _ => return Err(syntax.into()),
+        };
+        self.check_bindable(&var, "a loop variable")?;
+        if !matches!(iter.next(), Some(AliceToken::IdentOrKeyw(iok)) if iok == "in") {
+            return Err(syntax.into());
+        }
+        let start = self.gobble_range_bound(iter, syntax)?;
+        if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::Range))) {
+            return Err(syntax.into());
+        }
+        let end = self.gobble_range_bound(iter, syntax)?;
+        if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::OpenB))) {
+            return Err("for statements requires body block { ... }".into());
+        }
+        let body = self
+            .gobble_block(iter)?
+            .into_iter()
+            .map(|b| box_to_rc(b))
+            .collect();
+        Ok(Box::new(ForStatement(ForContainer {
+            var,
+            start,
+            end,
+            body,
+        })))
+    }
+
+    fn gobble_range_bound(
+        &self,
+        iter: &mut TokenIter,
+        syntax: &str,
+    ) -> Result<Vec<Rc<dyn Statement>>, String> {
+        match iter.next() {
+            Some(tok) => Ok(self
+                .gobble_next(tok, iter)?
+                .into_iter()
+                .map(|b| box_to_rc(b))
+                .collect()),
+            None => Err(syntax.into()),
+        }
+    }
+
+    /// syntax:
+    /// let = "let", ident, ":", type, ["=", (literal | block)]
     /// where literal can also be sbuject to an @-conversion
     fn gobble_let(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
         if let (
@@ -136,42 +671,104 @@ impl AliceParser {
             Some(AliceToken::IdentOrKeyw(ty)),
         ) = (iter.next(), iter.next(), iter.next())
         {
-            if self.keywords.contains_key(ident) {
-                return Err(format!(
-                    "{ident} is a reserved keyword, can't bind a variable to it"
-                ));
+            self.check_bindable(ident, "a variable")?;
+            let ty_bit = type_bit(&AliceVal::for_type_name(ty)?);
+            let mut literal = None;
+            let mut init = None;
+            if let Some(AliceToken::Op(AliceOp::Eqs)) = iter.peek() {
+                iter.next();
+                match iter.next() {
+                    Some(AliceToken::Sep(AliceSeparator::OpenB)) => {
+                        init = Some(
+                            self.gobble_block(iter)?
+                                .into_iter()
+                                .map(|b| box_to_rc(b))
+                                .collect(),
+                        )
+                    }
+                    Some(tok) => match self.gobble_literal(tok, iter)? {
+                        Some(val) if type_bit(&val) == ty_bit => literal = Some(val),
+                        Some(val) => {
+                            return Err(format!(
+                                "'let {ident}: {ty}' can't be initialized with {} literal {val}, convert it with @{ty}",
+                                val.type_name()
+                            ))
+                        }
+                        None => {
+                            return Err(format!(
+                                "expected literal or block {{ ... }} after 'let {ident}: {ty} =', found '{tok}'"
+                            ))
+                        }
+                    },
+                    None => {
+                        return Err(format!(
+                            "expected literal or block {{ ... }} after 'let {ident}: {ty} ='"
+                        ))
+                    }
+                }
             }
             Ok(Box::new(LetStatement {
                 ident: ident.into(),
-                ty: type_bit(&AliceVal::for_type_name(ty)?),
-                literal: None,
+                ty: ty_bit,
+                literal,
+                init,
             }))
         } else {
-            Err("let syntax: 'let' ident ':' type ['=' literal]".into())
+            Err("let syntax: 'let' ident ':' type ['=' (literal | block)]".into())
+        }
+    }
+
+    /// parses a function definition and keeps its source for re-emission
+    fn gobble_fun(
+        &self,
+        iter: &mut TokenIter,
+        memoized: bool,
+    ) -> Result<Box<dyn Statement>, String> {
+        // the fun (and memoize) keyword was already consumed
+        let mut start = self.position(iter) - 1 - usize::from(memoized);
+        let mut statement = self.gobble_fun_def(iter)?;
+        if let Some(AliceToken::Doc(doc)) = start.checked_sub(1).map(|i| &self.tokens[i]) {
+            statement.fun.doc = Some(doc.clone());
+            start -= 1;
+        }
+        statement.fun.source = Some(self.source_of(start, self.position(iter)));
+        statement.fun.memoized = memoized;
+        Ok(Box::new(statement))
+    }
+
+    /// a function whose results are cached by argument values.
+    /// only correct for functions without side effects
+    /// syntax:
+    /// memoize = "memoize", fun
+    fn gobble_memoize(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
+        match iter.next() {
+            Some(AliceToken::IdentOrKeyw(iok)) if iok == crate::keyword::KW_FUN => {
+                self.gobble_fun(iter, true)
+            }
+            _ => Err("memoize has to be followed by a function definition".into()),
         }
     }
 
     // syntax:
     // fun = "fun", ident, [":", { type [","] }], ["->", type], block
-    fn gobble_fun(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
+    fn gobble_fun_def(&self, iter: &mut TokenIter) -> Result<FunStatement, String> {
         if let Some(AliceToken::IdentOrKeyw(ident)) = iter.next() {
+            self.check_bindable(ident, "a function")?;
             // case 1: no type signature at all
             if let Some(AliceToken::Sep(AliceSeparator::OpenB)) = iter.peek() {
                 iter.next();
                 let fun = AliceFun {
                     args: StackPattern(Vec::new()),
                     return_type: 0,
-                    body: self
-                        .gobble_block(iter)?
-                        .into_iter()
-                        .map(|b| box_to_rc(b))
-                        .collect(),
+                    source: None,
+                    memoized: false,
+                    doc: None,
+                    body: self.gobble_fun_body(ident, iter)?,
                 };
-                fun.type_check()?;
-                Ok(Box::new(FunStatement {
+                Ok(FunStatement {
                     ident: ident.clone(),
                     fun,
-                }))
+                })
             // case 2: no args but return type
             } else if let Some(AliceToken::Op(AliceOp::Sub)) = iter.peek() {
                 iter.next();
@@ -179,17 +776,15 @@ impl AliceParser {
                 let fun = AliceFun {
                     args: StackPattern(Vec::new()),
                     return_type,
-                    body: self
-                        .gobble_block(iter)?
-                        .into_iter()
-                        .map(|b| box_to_rc(b))
-                        .collect(),
+                    source: None,
+                    memoized: false,
+                    doc: None,
+                    body: self.gobble_fun_body(ident, iter)?,
                 };
-                fun.type_check()?;
-                Ok(Box::new(FunStatement {
+                Ok(FunStatement {
                     ident: ident.clone(),
                     fun,
-                }))
+                })
             // case 3: args + maybe return type
             } else if let Some(AliceToken::Sep(AliceSeparator::Colon)) = iter.next() {
                 let mut args = Vec::new();
@@ -222,17 +817,15 @@ impl AliceParser {
                 let fun = AliceFun {
                     args: StackPattern(args),
                     return_type,
-                    body: self
-                        .gobble_block(iter)?
-                        .into_iter()
-                        .map(|b| box_to_rc(b))
-                        .collect(),
+                    source: None,
+                    memoized: false,
+                    doc: None,
+                    body: self.gobble_fun_body(ident, iter)?,
                 };
-                fun.type_check()?;
-                Ok(Box::new(FunStatement {
+                Ok(FunStatement {
                     ident: ident.clone(),
                     fun,
-                }))
+                })
             } else {
                 Err("after `'fun' ident`, expected one of: `'->'` `':'` `'{'`".into())
             }
@@ -241,6 +834,24 @@ impl AliceParser {
         }
     }
 
+    /// the body of the function `ident`; `iter` is right after its opening "{".
+    /// hitting EOF is reported with the function and where its body starts, since that is
+    /// likely far away from the end of the file
+    fn gobble_fun_body(
+        &self,
+        ident: &str,
+        iter: &mut TokenIter,
+    ) -> Result<Vec<Rc<dyn Statement>>, String> {
+        let open = &self.locs[self.position(iter) - 1];
+        match self.gobble_block(iter) {
+            Ok(body) => Ok(body.into_iter().map(|b| box_to_rc(b)).collect()),
+            Err(e) if iter.peek().is_none() && e.starts_with("missing delimiter") => Err(format!(
+                "function '{ident}' is never closed: hit EOF while searching for the '}}' of the body opened at {open}"
+            )),
+            Err(e) => Err(e),
+        }
+    }
+
     fn parse_fun_return_after_dash(&self, iter: &mut TokenIter) -> Result<u32, String> {
         if !matches!(iter.next(), Some(AliceToken::Op(AliceOp::Gt))) {
             return Err(
@@ -256,25 +867,181 @@ impl AliceParser {
         }
     }
 
+    /// errors if the given identifier can't be bound because it is a keyword or
+    /// because a built-in word of the same name would always shadow it
+    fn check_bindable(&self, ident: &str, what: &str) -> Result<(), String> {
+        if ident == DISCARD {
+            Err(format!("{DISCARD} is reserved, can't bind {what} to it"))
+        } else if self.keywords.contains_key(ident) {
+            Err(format!(
+                "{ident} is a reserved keyword, can't bind {what} to it"
+            ))
+        } else if WORDS.contains(&ident) {
+            Err(format!(
+                "{ident} is a built-in word, can't bind {what} to it"
+            ))
+        } else if self.alias_target(ident).is_some() {
+            Err(format!("{ident} is an alias, can't bind {what} to it"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// a syntax error at the last token consumed from the given iterator,
+    /// which is the one the error was noticed at
+    fn error_at(&self, msg: String, iter: &TokenIter) -> AliceError {
+        let i = self.position(iter).saturating_sub(1);
+        AliceError {
+            stage: Stage::Parsing,
+            msg,
+            loc: self.locs[i].clone(),
+        }
+    }
+
+    /// index of the next token of the given iterator into self.tokens
+    fn position(&self, iter: &TokenIter) -> usize {
+        self.tokens.len() - iter.len()
+    }
+
+    /// renders the tokens in the given range back into source code
+    fn source_of(&self, start: usize, end: usize) -> String {
+        let mut src = String::new();
+        let mut prev: Option<&AliceToken> = None;
+        for tok in &self.tokens[start..end] {
+            if prev.is_some_and(|prev| !glued(prev, tok)) {
+                src.push(' ');
+            }
+            src.push_str(&tok.to_string());
+            prev = Some(tok);
+        }
+        src
+    }
+
+    /// syntax:
+    /// list = "[", statement*, "]"
+    /// where the values pushed by the statements become the elements
+    fn gobble_list(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
+        let body = self
+            .gobble_statements(iter, &AliceSeparator::CloseS)?
+            .into_iter()
+            .map(|b| box_to_rc(b))
+            .collect();
+        Ok(Box::new(ListStatement(body)))
+    }
+
     /// parses tokens into a vec until the closing "}" is found
     fn gobble_block(&self, iter: &mut TokenIter) -> Result<Vec<Box<dyn Statement>>, String> {
+        self.gobble_statements(iter, &AliceSeparator::CloseB)
+    }
+
+    /// parses tokens into a vec until the given terminator (consumed), which means one more
+    /// level of nesting
+    fn gobble_statements(
+        &self,
+        iter: &mut TokenIter,
+        terminator: &AliceSeparator,
+    ) -> Result<Vec<Box<dyn Statement>>, String> {
+        let depth = self.depth.get() + 1;
+        if depth > self.max_depth {
+            return Err(format!(
+                "blocks and lists are nested deeper than {} levels",
+                self.max_depth
+            ));
+        }
+        self.depth.set(depth);
         let mut vec: Vec<Box<dyn Statement>> = Vec::new();
+        let result = self.gobble_statements_until(
+            iter,
+            Some(terminator),
+            |msg, _| msg,
+            |s, _| {
+                vec.push(s);
+                Ok(())
+            },
+        );
+        self.depth.set(depth - 1);
+        result.map(|_| vec)
+    }
+
+    /// the statement loop shared by top level and block parsing. hands each statement and
+    /// the location of the token it starts at to `f`, until the given terminator (consumed)
+    /// or, if there is none, until EOF is reached. syntax errors are turned into `E` by
+    /// `on_error`, which gets the iterator at the token the error was found at
+    fn gobble_statements_until<E>(
+        &self,
+        iter: &mut TokenIter,
+        terminator: Option<&AliceSeparator>,
+        on_error: impl Fn(String, &TokenIter) -> E,
+        mut f: impl FnMut(Box<dyn Statement>, &Loc) -> Result<(), E>,
+    ) -> Result<(), E> {
         while let Some(tok) = iter.next() {
-            if matches!(tok, AliceToken::Sep(AliceSeparator::CloseB)) {
-                return Ok(vec);
+            if let AliceToken::Sep(sep) = tok {
+                if Some(sep) == terminator {
+                    return Ok(());
+                }
             }
-            vec.push(self.gobble_token(tok, iter)?);
+            let loc = &self.locs[self.position(iter) - 1];
+            let statements = match self.gobble_next(tok, iter) {
+                Ok(statements) => statements,
+                Err(msg) => return Err(on_error(msg, iter)),
+            };
+            for s in statements {
+                f(s, loc)?;
+            }
+        }
+        match terminator {
+            None => Ok(()),
+            Some(t) => Err(on_error(
+                format!("missing delimiter: hit EOF while searching for '{t}'"),
+                iter,
+            )),
+        }
+    }
+
+    /// parses the statement starting with the given token and any @ conversions applied to it.
+    /// empty for directives like alias that don't produce statements, for doc comments and
+    /// for `;`, which only marks the end of the previous statement, e. g. a conversion can't
+    /// be applied across it
+    fn gobble_next(
+        &self,
+        tok: &AliceToken,
+        iter: &mut TokenIter,
+    ) -> Result<Vec<Box<dyn Statement>>, String> {
+        // a doc comment is picked up by the function definition after it, if there is one
+        if matches!(
+            tok,
+            AliceToken::Sep(AliceSeparator::Semi) | AliceToken::Doc(_)
+        ) {
+            return Ok(Vec::new());
+        }
+        if matches!(tok, AliceToken::IdentOrKeyw(iok) if iok == KW_ALIAS) {
+            self.gobble_alias(iter)?;
+            return Ok(Vec::new());
+        }
+        let mut vec = vec![self.gobble_token(tok, iter)?];
+        while let Some(target) = self.maybe_at_conversion(iter)? {
+            vec.push(Box::new(ConvertStatement(target)));
+        }
+        let count = self.statements.get() + vec.len();
+        if count > self.max_statements {
+            return Err(format!(
+                "the program has more than {} statements",
+                self.max_statements
+            ));
         }
-        Err("missing delimiter: hit EOF while searching for '}'".into())
+        self.statements.set(count);
+        Ok(vec)
     }
 
-    fn maybe_gobble_statement(&self, ident: &String) -> Option<Box<dyn Statement>> {
-        match ident.as_str() {
+    fn maybe_gobble_statement(&self, ident: &str) -> Option<Box<dyn Statement>> {
+        // on_add_word
+        match ident {
             ST_PRINTLN => Some(Box::new(PrintlnStatement)),
             ST_PRINT => Some(Box::new(PrintStatement)),
             ST_PRINT_STACK => Some(Box::new(PrintStackStatement)),
             ST_EXIT => Some(Box::new(ExitStatement)),
             ST_OK_EXIT => Some(Box::new(OkExitStatement)),
+            ST_ABORT => Some(Box::new(AbortStatement)),
             ST_DROP => Some(Box::new(DropStatement)),
             ST_SWAP => Some(Box::new(SwapStatement)),
             ST_DUP => Some(Box::new(DupStatement)),
@@ -282,26 +1049,131 @@ impl AliceParser {
             ST_ROT => Some(Box::new(RotStatement)),
             ST_CLEAR => Some(Box::new(ClearStatement)),
             ST_READ_LINE => Some(Box::new(ReadInputStatement)),
+            ST_ARGS => Some(Box::new(ArgsStatement)),
+            ST_READ_FILE => Some(Box::new(ReadFileStatement)),
+            ST_WRITE_FILE => Some(Box::new(WriteFileStatement)),
+            ST_RAND => Some(Box::new(RandStatement)),
+            ST_NOW => Some(Box::new(NowStatement)),
+            ST_SLEEP => Some(Box::new(SleepStatement)),
+            ST_TO_INT => Some(Box::new(ConvertStatement(AliceVal::int()))),
+            ST_DUMP_JSON => Some(Box::new(DumpJsonStatement)),
+            ST_LEN => Some(Box::new(LenStatement)),
+            ST_BYTE_LEN => Some(Box::new(ByteLenStatement)),
+            ST_TO_JSON => Some(Box::new(ToJsonStatement)),
+            ST_CLAMP => Some(Box::new(ClampStatement)),
+            ST_INT_MAX => Some(Box::new(PushStatement(AliceVal::Int(Some(i64::MAX))))),
+            ST_INT_MIN => Some(Box::new(PushStatement(AliceVal::Int(Some(i64::MIN))))),
+            ST_FLOAT_MAX => Some(Box::new(PushStatement(AliceVal::Float(Some(f64::MAX))))),
+            ST_FLOAT_INF => Some(Box::new(PushStatement(AliceVal::Float(Some(
+                f64::INFINITY,
+            ))))),
+            ST_FLOAT_NAN => Some(Box::new(PushStatement(AliceVal::Float(Some(f64::NAN))))),
+            ST_FIELDS => Some(Box::new(FieldsStatement)),
+            ST_REVERSE => Some(Box::new(ReverseStatement)),
+            ST_CONTAINS => Some(Box::new(ContainsStatement)),
+            ST_INDEX_OF => Some(Box::new(IndexOfStatement)),
+            ST_REPLACE => Some(Box::new(ReplaceStatement)),
+            ST_TO_FIXED => Some(Box::new(ToFixedStatement)),
+            ST_TO_SCIENTIFIC => Some(Box::new(ToScientificStatement)),
+            ST_ZIP => Some(Box::new(ZipStatement)),
+            ST_ENUMERATE => Some(Box::new(EnumerateStatement)),
+            ST_COUNT => Some(Box::new(CountStatement)),
+            ST_ASSERT_STACK => Some(Box::new(AssertStackStatement)),
+            ST_IS_EVEN => Some(Box::new(IsEvenStatement)),
+            ST_IS_ODD => Some(Box::new(IsOddStatement)),
+            ST_IS_POSITIVE => Some(Box::new(IsPositiveStatement)),
+            ST_IS_NEGATIVE => Some(Box::new(IsNegativeStatement)),
+            ST_IS_ZERO => Some(Box::new(IsZeroStatement)),
+            ST_SLICE => Some(Box::new(SliceStatement)),
             _ => None,
         }
     }
 
+    /// syntax:
+    /// member_access = ".", ident
+    fn gobble_member_access(&self, iter: &mut TokenIter) -> Result<Box<dyn Statement>, String> {
+        match iter.next() {
+            Some(AliceToken::IdentOrKeyw(member)) => {
+                Ok(Box::new(MemberAccessStatement(member.clone())))
+            }
+            _ => Err("expected member name after '.', e. g. point .x".into()),
+        }
+    }
+
     fn gobble_ident(
         &self,
-        ident: &String,
+        ident: &str,
         iter: &mut TokenIter,
     ) -> Result<Box<dyn Statement>, String> {
-        // todo!: at conversion
         if let Some(AliceToken::Sep(AliceSeparator::OpenP)) = iter.peek() {
             iter.next();
-            if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::CloseP))) {
-                Err("closing parentheses in function call missing!".into())
-            } else {
-                Ok(Box::new(ExecuteFunStatement(ident.clone())))
+            match iter.peek() {
+                Some(AliceToken::Sep(AliceSeparator::CloseP)) => {
+                    iter.next();
+                    Ok(Box::new(ExecuteFunStatement(ident.into())))
+                }
+                Some(AliceToken::IdentOrKeyw(_)) => self.gobble_construct(ident, iter),
+                _ => Err("closing parentheses in function call missing!".into()),
             }
         } else {
-            Ok(Box::new(PushFromTableStatement(ident.clone())))
+            let loc = self.locs[self.position(iter) - 1].clone();
+            Ok(Box::new(PushFromTableStatement(ident.into(), loc)))
+        }
+    }
+
+    /// constructs an object by member names, the left out members taking their defaults.
+    /// the opening parenthesis was already consumed.
+    /// syntax:
+    /// construct = ident, "(", { ident, ":", (literal | block) }, ")"
+    fn gobble_construct(
+        &self,
+        ty: &str,
+        iter: &mut TokenIter,
+    ) -> Result<Box<dyn Statement>, String> {
+        let mut members: Vec<(String, Vec<Rc<dyn Statement>>)> = Vec::new();
+        loop {
+            let member = match iter.next() {
+                Some(AliceToken::Sep(AliceSeparator::CloseP)) => break,
+                Some(AliceToken::IdentOrKeyw(member)) => member,
+                Some(tok) => {
+                    return Err(format!(
+                        "expected member name or ')' in construction of {ty}, found '{tok}'"
+                    ))
+                }
+                None => return Err(format!("hit EOF while parsing construction of {ty}")),
+            };
+            if members.iter().any(|(m, _)| m == member) {
+                return Err(format!("member {member} of {ty} is given twice"));
+            }
+            if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::Colon))) {
+                return Err(format!("expected ':' after member {member} of {ty}"));
+            }
+            let init = match iter.next() {
+                Some(AliceToken::Sep(AliceSeparator::OpenB)) => self
+                    .gobble_block(iter)?
+                    .into_iter()
+                    .map(|b| box_to_rc(b))
+                    .collect(),
+                Some(tok) => match self.gobble_literal(tok, iter)? {
+                    Some(val) => vec![Rc::new(PushStatement(val)) as Rc<dyn Statement>],
+                    None => {
+                        return Err(format!(
+                            "expected literal or block {{ ... }} after '{member}:', found '{tok}'"
+                        ))
+                    }
+                },
+                None => {
+                    return Err(format!(
+                        "expected literal or block {{ ... }} after '{member}:'"
+                    ))
+                }
+            };
+            members.push((member.clone(), init));
         }
+        Ok(Box::new(ConstructStatement {
+            ty: ty.into(),
+            members,
+        }))
     }
 
     fn gobble_string_literal(
@@ -309,43 +1181,99 @@ impl AliceParser {
         s: &String,
         iter: &mut TokenIter,
     ) -> Result<Box<dyn Statement>, String> {
+        if s.contains("${") {
+            // conversions of interpolated strings happen at runtime
+            return Ok(Box::new(InterpolateStatement(parse_interpolation(s)?)));
+        }
         Ok(Box::new(PushStatement(
             match self.maybe_at_conversion(iter) {
                 Ok(Some(AliceVal::String(_))) => AliceVal::String(Some(s.to_string())),
-                Ok(Some(_)) => todo!(),
+                Ok(Some(target)) => AliceVal::String(Some(s.to_string())).convert(&target)?,
                 Ok(None) => AliceVal::String(Some(s.to_string())),
                 Err(e) => return Err(e),
             },
         )))
     }
 
+    fn gobble_char_literal(
+        &self,
+        c: char,
+        iter: &mut TokenIter,
+    ) -> Result<Box<dyn Statement>, String> {
+        let val = AliceVal::Char(Some(c));
+        Ok(Box::new(PushStatement(
+            match self.maybe_at_conversion(iter)? {
+                Some(target) => val.convert(&target)?,
+                None => val,
+            },
+        )))
+    }
+
     fn gobble_number_literal(
         &self,
         f: f64,
         dec: bool,
         iter: &mut TokenIter,
     ) -> Result<Box<dyn Statement>, String> {
-        Ok(Box::new(PushStatement(
-            match self.maybe_at_conversion(iter) {
-                Ok(Some(AliceVal::Float(_))) => AliceVal::Float(Some(f)),
-                Ok(Some(AliceVal::Int(_))) => AliceVal::Int(Some(f as i64)),
-                Ok(Some(AliceVal::String(_))) => AliceVal::String(Some(f.to_string())),
-                Ok(None) => {
-                    if dec {
-                        AliceVal::Float(Some(f))
-                    } else {
-                        AliceVal::Int(Some(f as i64))
-                    }
-                }
-                Ok(Some(val)) => {
-                    return Err(format!(
-                        "cannot convert number literal to {}",
-                        val.type_name()
-                    ))
+        if matches!(iter.peek(), Some(AliceToken::IdentOrKeyw(w)) if w == ST_ROTN) {
+            iter.next();
+            if dec || f < 1.0 {
+                return Err(format!("{ST_ROTN} needs a positive int count, found {f}"));
+            }
+            return Ok(Box::new(RotNStatement(f as usize)));
+        }
+        Ok(Box::new(PushStatement(self.number_literal(f, dec, iter)?)))
+    }
+
+    /// the value of a number literal, applying an @ conversion directly to the number
+    /// so that e. g. a big int literal doesn't lose precision by going through another type
+    fn number_literal(&self, f: f64, dec: bool, iter: &mut TokenIter) -> Result<AliceVal, String> {
+        Ok(match self.maybe_at_conversion(iter) {
+            Ok(Some(AliceVal::Float(_))) => AliceVal::Float(Some(f)),
+            Ok(Some(AliceVal::Int(_))) => AliceVal::Int(Some(f as i64)),
+            Ok(Some(AliceVal::String(_))) => AliceVal::String(Some(f.to_string())),
+            Ok(Some(target @ AliceVal::Char(_))) if !dec => {
+                AliceVal::Int(Some(f as i64)).convert(&target)?
+            }
+            Ok(None) => {
+                if dec {
+                    AliceVal::Float(Some(f))
+                } else {
+                    AliceVal::Int(Some(f as i64))
                 }
-                Err(e) => return Err(e),
+            }
+            Ok(Some(val)) => {
+                return Err(format!(
+                    "cannot convert number literal to {}",
+                    val.type_name()
+                ))
+            }
+            Err(e) => return Err(e),
+        })
+    }
+
+    /// the value of the given literal token and any @ conversions applied to it,
+    /// None if the token isn't a literal. interpolated strings aren't literals
+    fn gobble_literal(
+        &self,
+        tok: &AliceToken,
+        iter: &mut TokenIter,
+    ) -> Result<Option<AliceVal>, String> {
+        let mut val = match tok {
+            AliceToken::Number(f, dec) => self.number_literal(*f, *dec, iter)?,
+            AliceToken::String(s) if !s.contains("${") => AliceVal::String(Some(s.clone())),
+            AliceToken::Char(c) => AliceVal::Char(Some(*c)),
+            AliceToken::IdentOrKeyw(iok) => match self.keywords.get(iok) {
+                Some(Keyword::True) => AliceVal::Bool(Some(true)),
+                Some(Keyword::False) => AliceVal::Bool(Some(false)),
+                _ => return Ok(None),
             },
-        )))
+            _ => return Ok(None),
+        };
+        while let Some(target) = self.maybe_at_conversion(iter)? {
+            val = val.convert(&target)?;
+        }
+        Ok(Some(val))
     }
 
     fn gobble_operator(
@@ -366,7 +1294,9 @@ impl AliceParser {
             AliceOp::Sub => Box::new(SubStatement),
             AliceOp::Mul => Box::new(MulStatement),
             AliceOp::Div => Box::new(DivStatement),
-            AliceOp::Pow => Box::new(PowStatement),
+            AliceOp::Pow => Box::new(PowStatement {
+                overflow_to_float: self.pow_overflow_to_float,
+            }),
             AliceOp::Mod => Box::new(ModStatement),
             AliceOp::Gt => {
                 if follows_eqs_op() {
@@ -374,20 +1304,26 @@ impl AliceParser {
                 } else {
                     Box::new(GtStatement)
                 }
-            },
+            }
             AliceOp::Lt => {
                 if follows_eqs_op() {
                     Box::new(LtEqsStatement)
                 } else {
                     Box::new(LtStatement)
                 }
-            },
-            AliceOp::Bang => todo!(),
+            }
+            AliceOp::Bang => {
+                if follows_eqs_op() {
+                    Box::new(NotEqsStatement)
+                } else {
+                    return Err("'!' is only valid as part of '!='".into());
+                }
+            }
             AliceOp::Eqs => {
                 if follows_eqs_op() {
                     Box::new(EqsStatement)
                 } else {
-                    todo!()
+                    return Err("'=' is only valid as part of '=='".into());
                 }
             }
         })
@@ -401,22 +1337,165 @@ impl AliceParser {
         match iter.peek() {
             Some(AliceToken::Sep(AliceSeparator::At)) => {
                 iter.next();
-                match iter.next() {
-                    None => Err("missing target type for @ conversion".into()),
-                    Some(AliceToken::IdentOrKeyw(iok)) => {
-                        if let Ok(ty) = AliceVal::for_type_name(iok) {
-                            Ok(Some(ty))
-                        } else {
-                            Err(format!("unexpected token '{iok}' that is not a type; @ conversion expects target type"))
-                        }
-                    }
-                    Some(tok) => Err(format!(
-                        "unexpected token {tok:?}; @ conversion expects target type"
-                    )),
-                }
+                self.gobble_type(iter)
+                    .map(Some)
+                    .map_err(|e| format!("{e}; @ conversion expects target type"))
             }
 
             None | Some(_) => Ok(None),
         }
     }
+
+    /// parses a type into a value of that type to be used as a prototype.
+    /// syntax:
+    /// type = ident | "[", type, "]"
+    fn gobble_type(&self, iter: &mut TokenIter) -> Result<AliceVal, String> {
+        match iter.next() {
+            None => Err("missing type".into()),
+            Some(AliceToken::IdentOrKeyw(iok))
+                if matches!(self.keywords.get(iok), Some(Keyword::True | Keyword::False)) =>
+            {
+                Err(format!(
+                    "'{iok}' is a boolean literal, not a type; to convert to a boolean write @bool"
+                ))
+            }
+            Some(AliceToken::IdentOrKeyw(iok)) if self.keywords.contains_key(iok) => {
+                Err(format!("'{iok}' is a reserved keyword, not a type"))
+            }
+            Some(AliceToken::IdentOrKeyw(iok)) => AliceVal::for_type_name(iok)
+                .map_err(|_| format!("unexpected token '{iok}' that is not a type")),
+            Some(AliceToken::Sep(AliceSeparator::OpenS)) => {
+                let depth = self.depth.get() + 1;
+                if depth > self.max_depth {
+                    return Err(format!(
+                        "list types are nested deeper than {} levels",
+                        self.max_depth
+                    ));
+                }
+                self.depth.set(depth);
+                let elem = self.gobble_type(iter);
+                self.depth.set(depth - 1);
+                let elem = elem?;
+                if !matches!(iter.next(), Some(AliceToken::Sep(AliceSeparator::CloseS))) {
+                    return Err("missing ']' after list element type".into());
+                }
+                Ok(AliceVal::list(type_bit(&elem), Vec::new()))
+            }
+            Some(tok) => Err(format!("unexpected token {tok:?} that is not a type")),
+        }
+    }
+}
+
+/// splits a string literal into literal parts and `${ident}` interpolations
+fn parse_interpolation(s: &str) -> Result<Vec<StringPart>, String> {
+    let mut parts = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        if start > 0 {
+            parts.push(StringPart::Literal(rest[..start].into()));
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("unterminated interpolation '${{' in string \"{s}\""))?;
+        let ident = after[..end].trim();
+        if ident.is_empty() {
+            return Err(format!("empty interpolation '${{}}' in string \"{s}\""));
+        }
+        parts.push(StringPart::Var(ident.into()));
+        rest = &after[end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(StringPart::Literal(rest.into()));
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::AliceLexer;
+
+    /// parses and type checks the source, returning the error message if it fails
+    fn parse(src: &str) -> Result<Vec<Box<dyn Statement>>, String> {
+        let tokens = AliceLexer::new(src.into(), "test".into())
+            .tokenize()
+            .map_err(|e| AliceError::from(e).to_string())?;
+        AliceParser::new(tokens)
+            .parse(None)
+            .map_err(|e| e.to_string())
+    }
+
+    fn parse_err(src: &str) -> String {
+        match parse(src) {
+            Ok(_) => panic!("parsing succeeded: {src}"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn variables_cant_shadow_built_in_words() {
+        assert!(parse_err("let dup: int = 1").contains("dup is a built-in word"));
+        assert!(parse_err("fun swap { }").contains("swap is a built-in word"));
+    }
+
+    #[test]
+    fn conversions_to_keywords_name_the_keyword() {
+        assert!(parse_err("5@let").contains("'let' is a reserved keyword, not a type"));
+    }
+
+    #[test]
+    fn conversions_chain_and_need_a_type() {
+        assert!(parse("5@float@int println").is_ok());
+        assert!(parse_err("5@").contains("missing type"));
+    }
+
+    #[test]
+    fn conversions_to_bool_literals_suggest_bool() {
+        assert!(parse_err("5@true").contains("'true' is a boolean literal, not a type"));
+        assert!(parse_err("5@false").contains("to convert to a boolean write @bool"));
+    }
+
+    #[test]
+    fn functions_without_parens_suggest_a_call() {
+        assert!(parse_err("fun f { } f").contains("did you mean 'f()'?"));
+        assert!(parse("fun f { } f()").is_ok());
+    }
+
+    #[test]
+    fn lone_comparison_chars_are_syntax_errors() {
+        assert!(parse_err("1 let x: int x = 1").contains("'=' is only valid as part of '=='"));
+        assert!(parse_err("1 ! 1").contains("'!' is only valid as part of '!='"));
+        assert!(parse("1 1 == println 1 1 != println").is_ok());
+    }
+
+    #[test]
+    fn deeply_nested_blocks_fail_cleanly() {
+        let nested = |depth: usize| "true if { ".repeat(depth) + &"} ".repeat(depth);
+        assert!(parse_err(&nested(100_000)).contains("nested deeper than 256 levels"));
+        assert!(parse(&nested(10)).is_ok());
+        let tokens = AliceLexer::new(nested(10), "test".into())
+            .tokenize()
+            .unwrap();
+        let e = AliceParser::new(tokens)
+            .max_depth(5)
+            .parse(None)
+            .err()
+            .unwrap();
+        assert!(e.to_string().contains("nested deeper than 5 levels"));
+    }
+
+    #[test]
+    fn lone_underscore_is_reserved() {
+        assert!(parse_err("_ println").contains("_ is reserved"));
+        assert!(parse_err("let _: int = 1").contains("_ is reserved"));
+        assert!(parse("let _foo: int = 1 _foo println").is_ok());
+    }
+
+    #[test]
+    fn unclosed_function_bodies_name_the_function() {
+        assert!(parse_err("fun f { 1 2 +").contains(
+            "function 'f' is never closed: hit EOF while searching for the '}' of the body opened at test:1:7"
+        ));
+    }
 }
@@ -1,58 +1,341 @@
 use crate::object::*;
+use crate::utils::strip_digit_separators;
 use std::collections::HashMap;
 
 pub const TYPE_STRING: &str = "string";
 pub const TYPE_BOOL: &str = "bool";
 pub const TYPE_INT: &str = "int";
 pub const TYPE_FLOAT: &str = "float";
+pub const TYPE_CHAR: &str = "char";
 pub const TYPE_OBJECT: &str = "object";
 pub const TYPE_FUNCTION: &str = "function";
+pub const TYPE_LIST: &str = "list";
+
+/// initial capacity of a default AliceStack. the stack grows beyond it as needed;
+/// the capacity only saves reallocations for programs that keep many values around
+pub const DEFAULT_STACK_CAPACITY: usize = 64;
+/// initial capacity of a default AliceTable, growing as needed like the stack
+pub const DEFAULT_TABLE_CAPACITY: usize = 32;
+/// how deeply function calls may nest by default, low enough to error before the
+/// interpreter itself runs out of stack
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
 
 #[derive(Debug)]
 pub struct AliceStack {
     pub stack: Vec<AliceVal>,
+    /// where each value on the stack came from, parallel to `stack`.
+    /// None unless tracing is enabled, see AliceStack::enable_trace
+    pub provenance: Option<Vec<Provenance>>,
+    /// provenance given to values pushed by the currently executing statement
+    pub origin: Provenance,
+}
+
+/// the statement that pushed a value onto the stack
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Provenance {
+    /// kind of the statement, see Statement::kind
+    pub kind: &'static str,
+    /// 1-based index of the top level statement that was executing, 0 if unknown
+    pub statement: usize,
 }
 
+/// a saved state of an AliceStack, see AliceStack::snapshot
+#[derive(Debug, Clone)]
+pub struct StackSnapshot(Vec<AliceVal>, Option<Vec<Provenance>>);
+
 #[derive(Debug)]
 pub struct AliceTable {
     pub vars: HashMap<String, AliceVal>,
+    /// results of memoized functions by function name and serialized arguments
+    pub memo: HashMap<String, HashMap<String, Option<AliceVal>>>,
+    /// declared object types by name
+    pub objects: HashMap<String, std::rc::Rc<AliceObjType>>,
+}
+
+/// state of the running program besides the stack and the variable table
+#[derive(Debug)]
+pub struct AliceContext {
+    /// command line arguments following the script path
+    pub args: Vec<String>,
+    /// file system used by the file I/O statements
+    pub fs: Box<dyn AliceFs>,
+    /// false if statements are not allowed to access the host (files, environment)
+    pub io_allowed: bool,
+    /// source of randomness for the rand statement
+    pub rng: AliceRng,
+    /// source of the current time and sleeping for the now and sleep statements
+    pub clock: Box<dyn AliceClock>,
+    /// execution counts and times per statement kind, if profiling is enabled
+    pub profile: Option<Profile>,
+    /// how many function calls are currently executing
+    pub call_depth: usize,
+    /// calling a function beyond this depth is an error, see DEFAULT_MAX_CALL_DEPTH
+    pub max_call_depth: usize,
 }
 
+/// execution count and total time per statement kind.
+/// times are inclusive, e. g. a function call contains the time of its body
+#[derive(Debug, Default)]
+pub struct Profile {
+    pub entries: HashMap<&'static str, (u64, std::time::Duration)>,
+}
+
+/// time source of a running program, abstracted so that it can be replaced by a fixed clock
+pub trait AliceClock: std::fmt::Debug {
+    /// milliseconds since the unix epoch
+    fn now_millis(&self) -> i64;
+    /// blocks for the given amount of milliseconds
+    fn sleep_millis(&mut self, millis: u64);
+}
+
+/// the system clock of the host
+#[derive(Debug)]
+pub struct SystemClock;
+
+/// small seedable pseudo random number generator (xorshift64*)
+#[derive(Debug, Clone)]
+pub struct AliceRng {
+    state: u64,
+}
+
+/// file system access of a running program, abstracted so that it can be sandboxed
+pub trait AliceFs: std::fmt::Debug {
+    fn read(&self, path: &str) -> Result<String, String>;
+    fn write(&mut self, path: &str, content: &str) -> Result<(), String>;
+}
+
+/// the actual file system of the host
+#[derive(Debug)]
+pub struct StdFs;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AliceVal {
     String(Option<String>),
     Bool(Option<bool>),
     Int(Option<i64>),
     Float(Option<f64>),
+    Char(Option<char>),
     Object(Option<AliceObj>),
     Function(Option<AliceFun>),
+    List(Option<AliceList>),
+}
+
+/// a homogeneous list; the element type is kept so that empty lists still have a
+/// well-defined type bit
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliceList {
+    /// possible values defined in type_check.rs
+    pub elem_type: u32,
+    pub vals: Vec<AliceVal>,
+}
+
+impl AliceContext {
+    pub fn new(args: Vec<String>) -> Self {
+        Self {
+            args,
+            fs: Box::new(StdFs),
+            io_allowed: true,
+            rng: AliceRng::from_time(),
+            clock: Box::new(SystemClock),
+            profile: None,
+            call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+        }
+    }
+
+    /// errors if I/O is disabled; to be called by every statement accessing the host
+    pub fn require_io(&self, word: &str) -> Result<(), String> {
+        if self.io_allowed {
+            Ok(())
+        } else {
+            Err(format!("'{word}' is not allowed: I/O is disabled"))
+        }
+    }
+}
+
+impl Default for AliceContext {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl Profile {
+    pub fn record(&mut self, kind: &'static str, elapsed: std::time::Duration) {
+        let entry = self.entries.entry(kind).or_default();
+        entry.0 += 1;
+        entry.1 += elapsed;
+    }
+
+    /// one line per statement kind, the most time consuming first
+    pub fn summary(&self) -> String {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by(|a, b| b.1 .1.cmp(&a.1 .1).then(a.0.cmp(b.0)));
+        let mut s = String::new();
+        for (kind, (count, total)) in entries {
+            s.push_str(&format!(
+                "[profile] {kind:<24} {count:>10}x {:>12.3} ms\n",
+                total.as_micros() as f64 / 1000f64
+            ));
+        }
+        s
+    }
+}
+
+impl AliceRng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on a zero state
+        Self {
+            state: if seed == 0 { 0x9E3779B97F4A7C15 } else { seed },
+        }
+    }
+
+    /// seeded from the current system time
+    pub fn from_time() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self::new(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    /// uniformly distributed in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl AliceClock for SystemClock {
+    fn now_millis(&self) -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0)
+    }
+
+    fn sleep_millis(&mut self, millis: u64) {
+        std::thread::sleep(std::time::Duration::from_millis(millis));
+    }
+}
+
+impl AliceFs for StdFs {
+    fn read(&self, path: &str) -> Result<String, String> {
+        std::fs::read_to_string(path).map_err(|e| format!("cannot read file {path}: {e}"))
+    }
+
+    fn write(&mut self, path: &str, content: &str) -> Result<(), String> {
+        std::fs::write(path, content).map_err(|e| format!("cannot write file {path}: {e}"))
+    }
+}
+
+impl Default for AliceStack {
+    fn default() -> Self {
+        Self::new(DEFAULT_STACK_CAPACITY)
+    }
 }
 
 impl AliceStack {
     pub fn new(capacity: usize) -> Self {
         Self {
             stack: Vec::with_capacity(capacity),
+            provenance: None,
+            origin: Provenance::default(),
         }
     }
 
+    /// starts tracking the provenance of every value pushed from now on.
+    /// values already on the stack get a default provenance
+    pub fn enable_trace(&mut self) {
+        self.provenance = Some(vec![Provenance::default(); self.stack.len()]);
+    }
+
     pub fn push(&mut self, val: AliceVal) {
         self.stack.push(val);
+        if let Some(provenance) = &mut self.provenance {
+            provenance.push(self.origin);
+        }
+    }
+
+    /// the provenance of the value at the given offset from the head, if tracing is enabled
+    pub fn provenance_of(&self, offset: usize) -> Option<Provenance> {
+        let provenance = self.provenance.as_ref()?;
+        provenance
+            .len()
+            .checked_sub(1 + offset)
+            .map(|i| provenance[i])
+    }
+
+    /// moves the value at the given offset from the head on top, keeping its provenance
+    pub fn move_to_top(&mut self, offset: usize) {
+        let i = self.stack.len() - 1 - offset;
+        let val = self.stack.remove(i);
+        self.stack.push(val);
+        if let Some(provenance) = &mut self.provenance {
+            let p = provenance.remove(i);
+            provenance.push(p);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.stack.clear();
+        if let Some(provenance) = &mut self.provenance {
+            provenance.clear();
+        }
     }
 
     pub fn get(&self, offset: usize) -> Option<&AliceVal> {
-        self.stack.get(self.stack.len() - 1 - offset)
+        self.stack
+            .len()
+            .checked_sub(1 + offset)
+            .and_then(|i| self.stack.get(i))
     }
 
     pub fn remove(&mut self, offset: usize) -> AliceVal {
-        self.stack.remove(self.stack.len() - 1 - offset)
+        let i = self.stack.len() - 1 - offset;
+        if let Some(provenance) = &mut self.provenance {
+            provenance.remove(i);
+        }
+        self.stack.remove(i)
     }
 
     pub fn size(&self) -> usize {
         self.stack.len()
     }
 
+    /// whether the whole stack holds exactly the given values, bottom first.
+    /// values are compared like `==` does, so NaN never equals itself and 1 equals 1.0
+    pub fn equals_values(&self, expected: &[AliceVal]) -> bool {
+        self.stack.len() == expected.len()
+            && self
+                .stack
+                .iter()
+                .zip(expected)
+                .all(|(a, b)| a.eq_promoted(b))
+    }
+
+    /// saves the current state so that it can be rolled back to using `restore`,
+    /// e. g. after speculatively executing statements that failed.
+    /// statements may pop below any given height, so this is a full copy
+    pub fn snapshot(&self) -> StackSnapshot {
+        StackSnapshot(self.stack.clone(), self.provenance.clone())
+    }
+
+    pub fn restore(&mut self, snapshot: StackSnapshot) {
+        self.stack = snapshot.0;
+        self.provenance = snapshot.1;
+    }
+
     pub fn pop(&mut self) -> Result<AliceVal, String> {
         if let Some(val) = self.stack.pop() {
+            if let Some(provenance) = &mut self.provenance {
+                provenance.pop();
+            }
             Ok(val)
         } else {
             Err("empty stack".into())
@@ -64,9 +347,9 @@ impl AliceStack {
     /// - `Ok(None)` if the stack was non emtpy but the head was of wrong type
     /// - `Err(_)` if the stack was empty
     pub fn pop_typed(&mut self, type_: &AliceVal) -> Result<Option<AliceVal>, String> {
-        if let Some(val) = self.stack.get(self.stack.len() - 1) {
+        if let Some(val) = self.stack.last() {
             if variant_eq(val, type_) {
-                Ok(Some(self.stack.pop().unwrap())) // unwrapping safe due to previous check
+                Ok(Some(self.pop().unwrap())) // unwrapping safe due to previous check
             } else {
                 Ok(None)
             }
@@ -76,10 +359,18 @@ impl AliceStack {
     }
 }
 
+impl Default for AliceTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_TABLE_CAPACITY)
+    }
+}
+
 impl AliceTable {
     pub fn new(capacity: usize) -> Self {
         Self {
             vars: HashMap::with_capacity(capacity),
+            memo: HashMap::new(),
+            objects: HashMap::new(),
         }
     }
 
@@ -157,6 +448,7 @@ impl AliceVal {
             TYPE_BOOL => Ok(Self::bool()),
             TYPE_INT => Ok(Self::int()),
             TYPE_FLOAT => Ok(Self::float()),
+            TYPE_CHAR => Ok(Self::char()),
             _ => Err(format!("unknown type name {s}")),
         }
     }
@@ -167,12 +459,153 @@ impl AliceVal {
             AliceVal::Bool(_) => TYPE_BOOL.into(),
             AliceVal::Int(_) => TYPE_INT.into(),
             AliceVal::Float(_) => TYPE_FLOAT.into(),
+            AliceVal::Char(_) => TYPE_CHAR.into(),
             AliceVal::Object(Some(o)) => o.type_name.clone(),
             AliceVal::Object(None) => TYPE_OBJECT.into(),
             AliceVal::Function(_) => TYPE_FUNCTION.into(),
+            AliceVal::List(_) => TYPE_LIST.into(),
+        }
+    }
+
+    /// parses an int the same way int literals are lexed, i. e. with `_` digit separators
+    pub fn parse_int(s: &str) -> Result<i64, String> {
+        strip_digit_separators(s, 10)?
+            .parse()
+            .map_err(|e| format!("cannot convert '{s}' to int: {e}"))
+    }
+
+    /// converts this value into a value of the type of `target`
+    pub fn convert(&self, target: &AliceVal) -> Result<AliceVal, String> {
+        Ok(match (self, target) {
+            (AliceVal::Int(Some(i)), AliceVal::Int(_)) => AliceVal::Int(Some(*i)),
+            (AliceVal::Int(Some(i)), AliceVal::Float(_)) => AliceVal::Float(Some(*i as f64)),
+            (AliceVal::Float(Some(f)), AliceVal::Int(_)) => AliceVal::Int(Some(*f as i64)),
+            (AliceVal::Float(Some(f)), AliceVal::Float(_)) => AliceVal::Float(Some(*f)),
+            (AliceVal::Bool(Some(b)), AliceVal::Int(_)) => AliceVal::Int(Some(i64::from(*b))),
+            (AliceVal::String(Some(s)), AliceVal::Int(_)) => {
+                AliceVal::Int(Some(Self::parse_int(s)?))
+            }
+            (AliceVal::String(Some(s)), AliceVal::Float(_)) => AliceVal::Float(Some(
+                strip_digit_separators(s, 10)?
+                    .parse()
+                    .map_err(|e| format!("cannot convert '{s}' to float: {e}"))?,
+            )),
+            (AliceVal::String(Some(s)), AliceVal::Char(_)) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => AliceVal::Char(Some(c)),
+                    _ => {
+                        return Err(format!(
+                            "cannot convert '{s}' to char: not exactly one character"
+                        ))
+                    }
+                }
+            }
+            (AliceVal::Char(Some(c)), AliceVal::Char(_)) => AliceVal::Char(Some(*c)),
+            (AliceVal::Char(Some(c)), AliceVal::Int(_)) => AliceVal::Int(Some(*c as i64)),
+            (AliceVal::Int(Some(i)), AliceVal::Char(_)) => AliceVal::Char(Some(
+                u32::try_from(*i)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| {
+                        format!("cannot convert {i} to char: not a unicode scalar value")
+                    })?,
+            )),
+            (val, AliceVal::String(_)) => AliceVal::String(Some(val.to_string())),
+            (val, target) => {
+                return Err(format!(
+                    "cannot convert {} to {}",
+                    val.type_name(),
+                    target.type_name()
+                ))
+            }
+        })
+    }
+
+    /// whether this is a binding without a value, e. g. a declared but unset object member
+    pub fn is_null(&self) -> bool {
+        match self {
+            AliceVal::String(val) => val.is_none(),
+            AliceVal::Bool(val) => val.is_none(),
+            AliceVal::Int(val) => val.is_none(),
+            AliceVal::Float(val) => val.is_none(),
+            AliceVal::Char(val) => val.is_none(),
+            AliceVal::Object(val) => val.is_none(),
+            AliceVal::Function(val) => val.is_none(),
+            AliceVal::List(val) => val.is_none(),
+        }
+    }
+
+    /// equality where an int and a float are compared by value, promoting the int.
+    /// list elements and object members are compared the same way
+    pub fn eq_promoted(&self, other: &AliceVal) -> bool {
+        match (self, other) {
+            (AliceVal::Int(Some(i)), AliceVal::Float(Some(f)))
+            | (AliceVal::Float(Some(f)), AliceVal::Int(Some(i))) => *i as f64 == *f,
+            (AliceVal::List(Some(a)), AliceVal::List(Some(b))) => {
+                a.vals.len() == b.vals.len()
+                    && a.vals.iter().zip(&b.vals).all(|(a, b)| a.eq_promoted(b))
+            }
+            (AliceVal::Object(Some(a)), AliceVal::Object(Some(b))) => {
+                a.type_hash == b.type_hash
+                    && a.members.len() == b.members.len()
+                    && a.members
+                        .iter()
+                        .all(|(name, val)| matches!(b.members.get(name), Some(other) if val.eq_promoted(other)))
+            }
+            (a, b) => a == b,
         }
     }
 
+    /// applies an arithmetic operation to two numbers, using `int_op` if both are ints
+    /// and `float_op` otherwise, promoting an int operand to float.
+    /// two lists of the same length are operated on element-wise.
+    /// `int_op` may fail, e. g. on division by zero
+    pub fn promote_binop(
+        a: AliceVal,
+        b: AliceVal,
+        int_op: impl Fn(i64, i64) -> Result<i64, String> + Copy,
+        float_op: impl Fn(f64, f64) -> f64 + Copy,
+    ) -> Result<AliceVal, String> {
+        Ok(match (a, b) {
+            (AliceVal::Int(Some(a)), AliceVal::Int(Some(b))) => AliceVal::Int(Some(int_op(a, b)?)),
+            (AliceVal::Float(Some(a)), AliceVal::Float(Some(b))) => {
+                AliceVal::Float(Some(float_op(a, b)))
+            }
+            (AliceVal::Int(Some(a)), AliceVal::Float(Some(b))) => {
+                AliceVal::Float(Some(float_op(a as f64, b)))
+            }
+            (AliceVal::Float(Some(a)), AliceVal::Int(Some(b))) => {
+                AliceVal::Float(Some(float_op(a, b as f64)))
+            }
+            (AliceVal::List(Some(a)), AliceVal::List(Some(b))) => {
+                if a.vals.len() != b.vals.len() {
+                    return Err(format!(
+                        "element-wise arithmetic on lists of different lengths {} and {}",
+                        a.vals.len(),
+                        b.vals.len()
+                    ));
+                }
+                let elem_type = crate::type_check::promote_numeric(a.elem_type, b.elem_type)
+                    .ok_or("element-wise arithmetic on lists of non-numbers")?;
+                let vals = a
+                    .vals
+                    .into_iter()
+                    .zip(b.vals)
+                    .map(|(a, b)| Self::promote_binop(a, b, int_op, float_op))
+                    .collect::<Result<Vec<_>, _>>()?;
+                AliceVal::list(elem_type, vals)
+            }
+            (a, b) => {
+                return Err(format!(
+                    "expected two numbers, found {} and {}",
+                    a.type_name(),
+                    b.type_name()
+                ))
+            }
+        })
+    }
+
     pub fn unchecked_string(&self) -> String {
         match self {
             AliceVal::String(s) => s.clone().as_ref().unwrap().clone(),
@@ -216,17 +649,180 @@ impl AliceVal {
     pub fn float() -> Self {
         Self::Float(None)
     }
+
+    pub fn char() -> Self {
+        Self::Char(None)
+    }
+
+    pub fn list(elem_type: u32, vals: Vec<AliceVal>) -> Self {
+        Self::List(Some(AliceList { elem_type, vals }))
+    }
+}
+
+/// how values are rendered as text, e. g. to produce literals of an emit target
+#[derive(Debug, Clone)]
+pub struct ValFormat {
+    pub true_literal: String,
+    pub false_literal: String,
+}
+
+impl Default for ValFormat {
+    fn default() -> Self {
+        Self {
+            true_literal: "true".into(),
+            false_literal: "false".into(),
+        }
+    }
+}
+
+/// a value together with the format to display it with, see AliceVal::formatted
+pub struct FormattedVal<'a> {
+    val: &'a AliceVal,
+    format: &'a ValFormat,
+}
+
+impl AliceVal {
+    pub fn formatted<'a>(&'a self, format: &'a ValFormat) -> FormattedVal<'a> {
+        FormattedVal { val: self, format }
+    }
+}
+
+impl std::fmt::Display for FormattedVal<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.val {
+            AliceVal::String(val) => {
+                write!(f, "{}", val.as_ref().expect("cannot print null binding"))
+            }
+            AliceVal::Bool(val) => write!(
+                f,
+                "{}",
+                if *val.as_ref().expect("cannot print null binding") {
+                    &self.format.true_literal
+                } else {
+                    &self.format.false_literal
+                }
+            ),
+            AliceVal::Int(val) => write!(f, "{}", val.as_ref().expect("cannot print null binding")),
+            AliceVal::Float(val) => {
+                write!(f, "{}", val.as_ref().expect("cannot print null binding"))
+            }
+            AliceVal::Char(val) => {
+                write!(f, "{}", val.as_ref().expect("cannot print null binding"))
+            }
+            AliceVal::Object(val) => {
+                let obj = val.as_ref().expect("cannot print null binding");
+                let mut names: Vec<_> = obj.members.keys().collect();
+                names.sort();
+                write!(f, "{} {{", obj.type_name)?;
+                for (i, name) in names.into_iter().enumerate() {
+                    write!(f, "{}{name}: ", if i == 0 { " " } else { ", " })?;
+                    let member = &obj.members[name];
+                    if member.is_null() {
+                        write!(f, "null")?;
+                    } else {
+                        write!(f, "{}", member.formatted(self.format))?;
+                    }
+                }
+                if obj.members.is_empty() {
+                    write!(f, "}}")
+                } else {
+                    write!(f, " }}")
+                }
+            }
+            AliceVal::Function(_fun) => write!(f, "function"),
+            AliceVal::List(val) => {
+                let list = val.as_ref().expect("cannot print null binding");
+                write!(f, "[")?;
+                for (i, v) in list.vals.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", v.formatted(self.format))?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for AliceVal {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            Self::String(val) => write!(f, "{}", val.as_ref().expect("cannot print null binding")),
-            Self::Bool(val) => write!(f, "{}", val.as_ref().expect("cannot print null binding")),
-            Self::Int(val) => write!(f, "{}", val.as_ref().expect("cannot print null binding")),
-            Self::Float(val) => write!(f, "{}", val.as_ref().expect("cannot print null binding")),
-            Self::Object(_o) => todo!(),
-            Self::Function(_fun) => write!(f, "function"),
+        write!(f, "{}", self.formatted(&ValFormat::default()))
+    }
+}
+
+impl std::fmt::Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.kind.is_empty() {
+            return write!(f, "pushed before tracing was enabled");
+        }
+        write!(f, "pushed by {}", self.kind)?;
+        if self.statement > 0 {
+            write!(f, " in statement {}", self.statement)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statement::{AddStatement, DivStatement, Statement};
+
+    #[test]
+    fn restoring_a_snapshot_undoes_a_failed_sequence() {
+        let mut stack = AliceStack::default();
+        let mut table = AliceTable::default();
+        let mut ctx = AliceContext::default();
+        stack.enable_trace();
+        stack.push(AliceVal::Int(Some(1)));
+        stack.push(AliceVal::Int(Some(2)));
+        stack.push(AliceVal::Int(Some(0)));
+        let snapshot = stack.snapshot();
+        // 2 0 + then 2 0 / fails after popping its operands
+        AddStatement.run(&mut stack, &mut table, &mut ctx).unwrap();
+        stack.push(AliceVal::Int(Some(0)));
+        assert!(DivStatement.run(&mut stack, &mut table, &mut ctx).is_err());
+        stack.restore(snapshot);
+        assert_eq!(
+            stack.stack,
+            vec![
+                AliceVal::Int(Some(1)),
+                AliceVal::Int(Some(2)),
+                AliceVal::Int(Some(0))
+            ]
+        );
+        assert_eq!(stack.provenance.map(|p| p.len()), Some(3));
+    }
+
+    #[test]
+    fn bools_render_with_the_given_literals() {
+        let format = ValFormat {
+            true_literal: "True".into(),
+            false_literal: "False".into(),
+        };
+        let list = AliceVal::List(Some(AliceList {
+            elem_type: crate::type_check::BOOL,
+            vals: vec![AliceVal::Bool(Some(true)), AliceVal::Bool(Some(false))],
+        }));
+        assert_eq!(
+            AliceVal::Bool(Some(true)).formatted(&format).to_string(),
+            "True"
+        );
+        assert_eq!(list.formatted(&format).to_string(), "[True False]");
+        assert_eq!(list.to_string(), "[true false]");
+    }
+
+    #[test]
+    fn stacks_and_tables_take_a_custom_capacity() {
+        let mut stack = AliceStack::new(1000);
+        assert!(stack.stack.capacity() >= 1000);
+        assert!(AliceTable::new(500).vars.capacity() >= 500);
+        assert!(AliceStack::default().stack.capacity() >= DEFAULT_STACK_CAPACITY);
+        // the capacity is no limit
+        for i in 0..2000 {
+            stack.push(AliceVal::Int(Some(i)));
         }
+        assert_eq!(stack.stack.len(), 2000);
     }
 }
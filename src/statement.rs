@@ -1,7 +1,9 @@
 use crate::flow::*;
+use crate::loc::Loc;
 use crate::object::*;
 use crate::runtime::*;
 use crate::type_check::*;
+use std::rc::Rc;
 
 pub trait Statement {
     fn in_pattern(&self) -> StackPattern {
@@ -14,7 +16,72 @@ pub trait Statement {
     fn out_pattern(&self) -> StackPattern {
         StackPattern(Vec::new())
     }
-    fn execute(&self, stack: &mut AliceStack, table: &mut AliceTable) -> Result<(), String>;
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String>;
+
+    /// true if execution never continues after this statement,
+    /// making the statements after it at the same level unreachable
+    fn diverges(&self) -> bool {
+        false
+    }
+
+    /// name of the kind of statement, used for profiling
+    fn kind(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// executes the statement, recording it if profiling is enabled.
+    /// to be used instead of calling execute directly
+    fn run(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        if stack.provenance.is_some() {
+            return self.run_traced(stack, table, ctx);
+        }
+        if ctx.profile.is_none() {
+            return self.execute(stack, table, ctx);
+        }
+        let t0 = std::time::Instant::now();
+        let result = self.execute(stack, table, ctx);
+        if let Some(profile) = &mut ctx.profile {
+            profile.record(self.kind(), t0.elapsed());
+        }
+        result
+    }
+
+    /// like run, but values pushed get this statement as their provenance and
+    /// errors name the provenance of the stack head
+    fn run_traced(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let outer = stack.origin;
+        let head = stack.provenance_of(0);
+        stack.origin.kind = self.kind();
+        let t0 = std::time::Instant::now();
+        let result = self.execute(stack, table, ctx);
+        if let Some(profile) = &mut ctx.profile {
+            profile.record(self.kind(), t0.elapsed());
+        }
+        stack.origin = outer;
+        match (result, head) {
+            // nested statements annotate first; the innermost head is the relevant one
+            (Err(e), Some(head)) if !e.contains(" (stack head ") => {
+                Err(format!("{e} (stack head {head})"))
+            }
+            (result, _) => result,
+        }
+    }
 }
 
 /// clones a literal onto the stack
@@ -35,16 +102,22 @@ pub struct ExitStatement;
 /// exits the program with an ok (0) exit code
 pub struct OkExitStatement;
 
+/// prints a message to stderr and exits the program with an exit code
+/// "message" code abort ->
+pub struct AbortStatement;
+
 /// drop the head of the stack
 pub struct DropStatement;
 
 /// swaps the two top most stack elements
 pub struct SwapStatement;
 
-/// duplicates the stacks head on top of itself
+/// duplicates the stacks head on top of itself.
+/// values have value semantics. duplicating an object shares its members instead of
+/// copying them, see AliceObj::members
 pub struct DupStatement;
 
-/// copies the second element on the stack on top
+/// copies the second element on the stack on top, with value semantics like dup
 /// a b over -> a b a'
 pub struct OverStatement;
 
@@ -52,6 +125,11 @@ pub struct OverStatement;
 /// a b c rot -> b c a
 pub struct RotStatement;
 
+/// moves the nth value from the top on top, where n is a literal right before the word;
+/// 3 rotn is rot
+/// a b c d 4 rotn -> b c d a
+pub struct RotNStatement(pub usize);
+
 /// adds the two top most stack elements
 pub struct AddStatement;
 
@@ -68,7 +146,13 @@ pub struct DivStatement;
 
 /// raises the second element to the power of the first
 /// a b ** = a^b
-pub struct PowStatement;
+pub struct PowStatement {
+    /// if true, int ** int results in a float, so that results too large for an int
+    /// don't error but lose precision instead
+    /// if false, a negative int exponent is a runtime error, since the type of int ** int
+    /// has to be known before running and can't depend on the exponent's sign
+    pub overflow_to_float: bool,
+}
 
 /// "modulos" the two top most stack elements in "reading" order:
 /// a b % = a % b
@@ -82,10 +166,13 @@ pub struct LetStatement {
     pub ident: String,
     pub ty: u32,
     pub literal: Option<AliceVal>,
+    /// a block pushing exactly the one value to bind, `let x: int = { 2 3 + }`
+    pub init: Option<Vec<Rc<dyn Statement>>>,
 }
 
-/// copies a variable's value from the table onto the stack
-pub struct PushFromTableStatement(pub String);
+/// copies a variable's value from the table onto the stack.
+/// knows where it is in the source, as it's an error if the variable doesn't exist
+pub struct PushFromTableStatement(pub String, pub Loc);
 
 /// binds a function
 pub struct FunStatement {
@@ -96,19 +183,57 @@ pub struct FunStatement {
 /// executes a function from the table
 pub struct ExecuteFunStatement(pub String);
 
+/// declares an object type and binds its constructor as a function of the same name
+pub struct ObjectStatement(pub Rc<AliceObjType>);
+
+/// pushes an object with the given members, the others taking the defaults of their
+/// declaration. each member is initialized by a literal or a block pushing one value
+/// Point(y: 1.5) -> Point { x: 0, y: 1.5 }, with object Point { x: int = 0 y: float }
+pub struct ConstructStatement {
+    /// name of the object type
+    pub ty: String,
+    pub members: Vec<(String, Vec<Rc<dyn Statement>>)>,
+}
+
+/// pops an object and pushes a copy of one of its members
+/// 3 1.5 Point() .x -> 3
+pub struct MemberAccessStatement(pub String);
+
 /// if statement
 pub struct IfStatement(pub IfContainer);
 
 /// if-else statement
 pub struct IfElseStatement(pub IfElseContainer);
 
+/// try-catch statement
+pub struct TryCatchStatement(pub TryCatchContainer);
+
+/// while statement
+pub struct WhileStatement(pub WhileContainer);
+
+/// for statement
+pub struct ForStatement(pub ForContainer);
+
+/// match statement
+pub struct MatchStatement(pub MatchContainer);
+
+/// a piece of an interpolated string
+pub enum StringPart {
+    Literal(String),
+    /// `${ident}`, replaced by the value of the variable
+    Var(String),
+}
+
+/// pushes a string built from literal parts and variable values: "x is ${x}"
+pub struct InterpolateStatement(pub Vec<StringPart>);
+
 /// reads a single line of input from the command line
 pub struct ReadInputStatement;
 
 /// compares the two topmost values for equality
 pub struct EqsStatement;
-///
-/// compares the two topmost values for equality
+
+/// compares the two topmost values for inequality
 pub struct NotEqsStatement;
 
 /// compares the two topmost (number) values in a > fashion
@@ -126,12 +251,152 @@ pub struct LtEqsStatement;
 /// negates the topmost bool
 pub struct NotStatement;
 
+/// pops two bools and pushes true if both are true.
+/// both operands have already been evaluated, so there is nothing to short-circuit
+pub struct AndStatement;
+
+/// pops two bools and pushes true if either is true.
+/// both operands have already been evaluated, so there is nothing to short-circuit
+pub struct OrStatement;
+
+/// pushes the command line arguments following the script path as a list of strings
+pub struct ArgsStatement;
+
+/// pops a path and pushes the contents of the file at that path
+pub struct ReadFileStatement;
+
+/// pops a content string and a path and writes the content to the file at that path
+/// "path" "content" writefile
+pub struct WriteFileStatement;
+
+/// pushes a random float in [0, 1)
+/// or, if the head is an int n, pops it and pushes a random int in [0, n)
+pub struct RandStatement;
+
+/// pushes the current unix time in milliseconds
+pub struct NowStatement;
+
+/// pops an int and sleeps for that many milliseconds
+pub struct SleepStatement;
+
+/// converts the head of the stack to the type of the given value
+pub struct ConvertStatement(pub AliceVal);
+
+/// prints the full stack as json for external tools
+pub struct DumpJsonStatement;
+
+/// pops a string or list and pushes its length; for strings that is the number of
+/// characters (unicode scalar values), not bytes, see ByteLenStatement
+pub struct LenStatement;
+
+/// pops a string and pushes the number of bytes of its utf-8 encoding,
+/// which is more than its len if it contains non-ascii characters
+pub struct ByteLenStatement;
+
+/// clamps a number into a range, promoting to float if any operand is a float
+/// value lo hi clamp -> value'
+pub struct ClampStatement;
+
+/// pops an object and pushes the names of its members as a list of strings, sorted by name
+/// object fields -> [string]
+pub struct FieldsStatement;
+
+/// pops a string or list and pushes it reversed
+/// [1 2 3] reverse -> [3 2 1]
+pub struct ReverseStatement;
+
+/// pops a value and a list and pushes whether the list contains the value
+/// [1 2 3] 2 contains -> true
+pub struct ContainsStatement;
+
+/// pops a needle and a haystack string and pushes the char index of the first
+/// occurrence of the needle, or -1 if there is none
+/// "héllo" "l" indexof -> 2
+pub struct IndexOfStatement;
+
+/// pops a replacement, a pattern and a source string and pushes the source with all
+/// occurrences of the pattern replaced, scanning left to right without overlaps.
+/// an empty pattern is an error
+/// "aaa" "aa" "b" replace -> "ba"
+pub struct ReplaceStatement;
+
+/// pops a precision and a float and pushes the float in fixed notation with that many
+/// decimal places
+/// 3.14159 2 tofixed -> "3.14"
+pub struct ToFixedStatement;
+
+/// pops a precision and a float and pushes the float in scientific notation with that
+/// many decimal places
+/// 1234.5 2 toscientific -> "1.23e3"
+pub struct ToScientificStatement;
+
+/// pops two lists and pushes a list of pairs of their elements, as long as the shorter one.
+/// the pairs are objects with the members first and second, see object::pair_type
+/// [1 2] ["a" "b" "c"] zip -> [Pair { first: 1, second: a } Pair { first: 2, second: b }]
+pub struct ZipStatement;
+
+/// pops a list of bools and pushes how many of them are true
+/// [true false true] count -> 2
+pub struct CountStatement;
+
+/// pops an int and pushes whether it is even
+/// -4 iseven -> true
+pub struct IsEvenStatement;
+
+/// pops an int and pushes whether it is odd
+/// -3 isodd -> true
+pub struct IsOddStatement;
+
+/// pops a number and pushes whether it is greater than zero
+/// 0.5 ispositive -> true
+pub struct IsPositiveStatement;
+
+/// pops a number and pushes whether it is less than zero
+/// -1 isnegative -> true
+pub struct IsNegativeStatement;
+
+/// pops a number and pushes whether it is zero
+/// 0.0 iszero -> true
+pub struct IsZeroStatement;
+
+/// pops an end index, a start index and a list and pushes the elements from start
+/// up to but excluding end
+/// [1 2 3 4] 1 3 slice -> [2 3]
+pub struct SliceStatement;
+
+/// pops a list and errors if the rest of the stack, bottom first, isn't equal to it
+/// 1 2 [1 2] assertstack -> 1 2
+pub struct AssertStackStatement;
+
+/// pops a list and pushes a list of pairs of each index and element, like zip
+/// ["a" "b"] enumerate -> [Pair { first: 0, second: a } Pair { first: 1, second: b }]
+pub struct EnumerateStatement;
+
+/// runs the body and collects the values it pushed into a list
+/// [1 2 3] -> [1 2 3]
+pub struct ListStatement(pub Vec<Rc<dyn Statement>>);
+
+/// pops any value and pushes it serialized as json
+pub struct ToJsonStatement;
+
+/// pops a json string and pushes the value it describes,
+/// which has to be of the type of the given value
+pub struct FromJsonStatement(pub AliceVal);
+
+/// pops a json string and pushes the object of the named type it describes
+pub struct FromJsonObjectStatement(pub String);
+
 impl Statement for PushStatement {
     fn out_pattern(&self) -> StackPattern {
         StackPattern::single(type_bit(&self.0))
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         stack.push(self.0.clone());
         Ok(())
     }
@@ -142,7 +407,12 @@ impl Statement for PrintlnStatement {
         StackPattern::any(1)
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         let val = stack.pop()?;
         println!("{val}");
         Ok(())
@@ -154,7 +424,12 @@ impl Statement for PrintStatement {
         StackPattern::any(1)
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         let val = stack.pop()?;
         print!("{val}");
         Ok(())
@@ -162,9 +437,17 @@ impl Statement for PrintStatement {
 }
 
 impl Statement for PrintStackStatement {
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
-        for val in &stack.stack {
-            println!("{val}");
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        for (i, val) in stack.stack.iter().enumerate() {
+            match stack.provenance_of(stack.size() - 1 - i) {
+                Some(provenance) => println!("{val}\t({provenance})"),
+                None => println!("{val}"),
+            }
         }
         Ok(())
     }
@@ -174,7 +457,17 @@ impl Statement for ExitStatement {
     fn in_pattern(&self) -> StackPattern {
         StackPattern::single(INT)
     }
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+
+    fn diverges(&self) -> bool {
+        true
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         // todo: redundant with type checker
         match stack.pop_typed(&AliceVal::int()) {
             Ok(Some(val)) => std::process::exit(val.unchecked_int() as i32),
@@ -185,7 +478,16 @@ impl Statement for ExitStatement {
 }
 
 impl Statement for OkExitStatement {
-    fn execute(&self, _stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn diverges(&self) -> bool {
+        true
+    }
+
+    fn execute(
+        &self,
+        _stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         std::process::exit(0);
     }
 }
@@ -195,7 +497,12 @@ impl Statement for DropStatement {
         StackPattern::any(1)
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         let _ = stack.pop();
         // type checker promises that stack operations can never fail
         Ok(())
@@ -206,14 +513,17 @@ impl Statement for SwapStatement {
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
         // need to dynamically generate type check patterns
         stack.required_size(2)?;
-        let second = stack.vals.remove(stack.vals.len() - 2);
-        stack.vals.push(second);
+        stack.move_to_top(1);
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
-        let second = stack.remove(1);
-        stack.push(second);
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        stack.move_to_top(1);
         Ok(())
     }
 }
@@ -221,13 +531,16 @@ impl Statement for SwapStatement {
 impl Statement for DupStatement {
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
         stack.required_size(1)?;
-        stack
-            .vals
-            .push(*stack.vals.get(stack.vals.len() - 1).unwrap()); // unwrapping safe due to previous check
+        stack.vals.push(*stack.vals.last().unwrap()); // unwrapping safe due to previous check
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         stack.push(stack.get(0).unwrap().clone()); // unuwrapping safe due to type checker
         Ok(())
     }
@@ -242,7 +555,12 @@ impl Statement for OverStatement {
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         stack.push(stack.get(1).unwrap().clone()); // unuwrapping safe due to type checker
         Ok(())
     }
@@ -251,13 +569,33 @@ impl Statement for OverStatement {
 impl Statement for RotStatement {
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
         stack.required_size(3)?;
-        let third = stack.vals.remove(stack.vals.len() - 3);
-        stack.vals.push(third);
+        stack.move_to_top(2);
+        Ok(())
+    }
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        stack.move_to_top(2);
+        Ok(())
+    }
+}
+
+impl Statement for RotNStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(self.0)?;
+        stack.move_to_top(self.0 - 1);
         Ok(())
     }
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
-        let third = stack.remove(2);
-        stack.push(third);
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        stack.move_to_top(self.0 - 1);
         Ok(())
     }
 }
@@ -270,43 +608,37 @@ impl Statement for AddStatement {
         let b = stack.pop().unwrap();
         let a = stack.pop().unwrap();
         stack.vals.push(match (a, b) {
-            (INT, INT) => INT,
-            (FLOAT, FLOAT) => FLOAT,
-            (INT, FLOAT) | (FLOAT, INT) => FLOAT,
             (STRING, STRING) => STRING,
-            _ => {
-                return Err(TypeCheckError(
-                    "+ only works on numbers and string+string concat".into(),
-                ))
-            }
+            _ => promote_numeric(a, b).ok_or_else(|| {
+                TypeCheckError::new("+ only works on numbers and string+string concat".into())
+            })?,
         });
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         let b = stack.pop()?;
         let a = stack.pop()?;
-        // all unwrapping is safe due to type checker
-        match (a, b) {
-            (AliceVal::Int(a), AliceVal::Int(b)) => {
-                stack.push(AliceVal::Int(Some(a.unwrap() + b.unwrap())))
-            }
-            (AliceVal::Float(a), AliceVal::Float(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() + b.unwrap())))
-            }
-            (AliceVal::Int(a), AliceVal::Float(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() as f64 + b.unwrap())))
+        stack.push(match (a, b) {
+            (AliceVal::String(Some(mut a)), AliceVal::String(Some(b))) => {
+                a.push_str(&b);
+                AliceVal::String(Some(a))
             }
-            (AliceVal::Float(a), AliceVal::Int(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() + b.unwrap() as f64)))
-            }
-            (AliceVal::String(a), AliceVal::String(b)) => stack.push(AliceVal::String(Some({
-                let mut s = a.unwrap().clone();
-                s.push_str(b.unwrap().as_str());
-                s
-            }))),
-            _ => (),
-        }
+            (a, b) => AliceVal::promote_binop(
+                a,
+                b,
+                |a, b| {
+                    a.checked_add(b)
+                        .ok_or_else(|| "integer overflow in +".to_string())
+                },
+                |a, b| a + b,
+            )?,
+        });
         Ok(())
     }
 }
@@ -317,148 +649,157 @@ impl Statement for SubStatement {
         stack.required_size(2)?;
         let b = stack.pop().unwrap();
         let a = stack.pop().unwrap();
-        stack.vals.push(match (a, b) {
-            (INT, INT) => INT,
-            (FLOAT, FLOAT) => FLOAT,
-            (INT, FLOAT) | (FLOAT, INT) => FLOAT,
-            _ => return Err(TypeCheckError("- only works on numbers".into())),
-        });
+        let ty = promote_numeric(a, b)
+            .ok_or_else(|| TypeCheckError::new("- only works on numbers".into()))?;
+        stack.vals.push(ty);
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         let b = stack.pop()?;
         let a = stack.pop()?;
-        // all unwrapping is safe due to type checker
-        match (a, b) {
-            (AliceVal::Int(a), AliceVal::Int(b)) => {
-                stack.push(AliceVal::Int(Some(a.unwrap() - b.unwrap())))
-            }
-            (AliceVal::Float(a), AliceVal::Float(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() - b.unwrap())))
-            }
-            (AliceVal::Int(a), AliceVal::Float(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() as f64 - b.unwrap())))
-            }
-            (AliceVal::Float(a), AliceVal::Int(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() - b.unwrap() as f64)))
-            }
-            _ => (),
-        }
+        stack.push(AliceVal::promote_binop(
+            a,
+            b,
+            |a, b| {
+                a.checked_sub(b)
+                    .ok_or_else(|| "integer overflow in -".to_string())
+            },
+            |a, b| a - b,
+        )?);
         Ok(())
     }
 }
 
-// works on number - number
 impl Statement for MulStatement {
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
         stack.required_size(2)?;
         let b = stack.pop().unwrap();
         let a = stack.pop().unwrap();
-        stack.vals.push(match (a, b) {
-            (INT, INT) => INT,
-            (FLOAT, FLOAT) => FLOAT,
-            (INT, FLOAT) | (FLOAT, INT) => FLOAT,
-            _ => return Err(TypeCheckError("* only works on numbers".into())),
-        });
+        let ty = promote_numeric(a, b)
+            .ok_or_else(|| TypeCheckError::new("* only works on numbers".into()))?;
+        stack.vals.push(ty);
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         let b = stack.pop()?;
         let a = stack.pop()?;
-        // all unwrapping is safe due to type checker
-        match (a, b) {
-            (AliceVal::Int(a), AliceVal::Int(b)) => {
-                stack.push(AliceVal::Int(Some(a.unwrap() * b.unwrap())))
-            }
-            (AliceVal::Float(a), AliceVal::Float(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() * b.unwrap())))
-            }
-            (AliceVal::Int(a), AliceVal::Float(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() as f64 * b.unwrap())))
-            }
-            (AliceVal::Float(a), AliceVal::Int(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() * b.unwrap() as f64)))
-            }
-            _ => (),
-        }
+        stack.push(AliceVal::promote_binop(
+            a,
+            b,
+            |a, b| {
+                a.checked_mul(b)
+                    .ok_or_else(|| "integer overflow in *".to_string())
+            },
+            |a, b| a * b,
+        )?);
         Ok(())
     }
 }
 
-// works on number - number
 impl Statement for DivStatement {
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
         stack.required_size(2)?;
         let b = stack.pop().unwrap();
         let a = stack.pop().unwrap();
-        stack.vals.push(match (a, b) {
-            (INT, INT) => INT,
-            (FLOAT, FLOAT) => FLOAT,
-            (INT, FLOAT) | (FLOAT, INT) => FLOAT,
-            _ => return Err(TypeCheckError("/ only works on numbers".into())),
-        });
+        let ty = promote_numeric(a, b)
+            .ok_or_else(|| TypeCheckError::new("/ only works on numbers".into()))?;
+        stack.vals.push(ty);
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         let b = stack.pop()?;
         let a = stack.pop()?;
-        // all unwrapping is safe due to type checker
-        match (a, b) {
-            (AliceVal::Int(a), AliceVal::Int(b)) => {
-                stack.push(AliceVal::Int(Some(a.unwrap() / b.unwrap())))
-            }
-            (AliceVal::Float(a), AliceVal::Float(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() / b.unwrap())))
-            }
-            (AliceVal::Int(a), AliceVal::Float(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() as f64 / b.unwrap())))
-            }
-            (AliceVal::Float(a), AliceVal::Int(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() / b.unwrap() as f64)))
-            }
-            _ => (),
-        }
+        // float division by zero gives inf or NaN
+        let int_div = |a: i64, b: i64| match b {
+            0 => Err("division by zero".to_string()),
+            _ => a
+                .checked_div(b)
+                .ok_or_else(|| "integer overflow in /".to_string()),
+        };
+        stack.push(AliceVal::promote_binop(a, b, int_div, |a, b| a / b)?);
         Ok(())
     }
 }
 
-// works on number - number
 impl Statement for PowStatement {
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
         stack.required_size(2)?;
         let b = stack.pop().unwrap();
         let a = stack.pop().unwrap();
         stack.vals.push(match (a, b) {
+            (INT, INT) if self.overflow_to_float => FLOAT,
             (INT, INT) => INT,
             (FLOAT, FLOAT) => FLOAT,
             (FLOAT, INT) => FLOAT,
             (INT, FLOAT) => {
-                return Err(TypeCheckError(
+                return Err(TypeCheckError::new(
                     "cannot raise an int to the power of a float".into(),
                 ))
             }
-            _ => return Err(TypeCheckError("** only works on numbers".into())),
+            _ => return Err(TypeCheckError::new("** only works on numbers".into())),
         });
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         let b = stack.pop()?;
         let a = stack.pop()?;
         // all unwrapping is safe due to type checker
         match (a, b) {
-            (AliceVal::Int(a), AliceVal::Int(b)) => stack.push(AliceVal::Int(Some(
-                a.unwrap().pow(b.unwrap().try_into().unwrap()),
-            ))),
+            (AliceVal::Int(Some(a)), AliceVal::Int(Some(b))) if self.overflow_to_float && b < 0 => {
+                stack.push(AliceVal::Float(Some((a as f64).powf(b as f64))))
+            }
+            (AliceVal::Int(Some(a)), AliceVal::Int(Some(b))) => {
+                let exp: u32 = b.try_into().map_err(|_| {
+                    format!(
+                        "negative exponent in {a} ** {b}: the result would be a float, \
+                         convert the base with @float"
+                    )
+                })?;
+                match a.checked_pow(exp) {
+                    Some(n) if self.overflow_to_float => {
+                        stack.push(AliceVal::Float(Some(n as f64)))
+                    }
+                    Some(n) => stack.push(AliceVal::Int(Some(n))),
+                    None if self.overflow_to_float => {
+                        stack.push(AliceVal::Float(Some((a as f64).powf(b as f64))))
+                    }
+                    None => {
+                        return Err(format!(
+                            "integer overflow: {a} ** {b} doesn't fit into an int"
+                        ))
+                    }
+                }
+            }
             (AliceVal::Float(a), AliceVal::Float(b)) => {
                 stack.push(AliceVal::Float(Some(a.unwrap().powf(b.unwrap()))))
             }
-            (AliceVal::Float(a), AliceVal::Int(b)) => stack.push(AliceVal::Float(Some(
-                a.unwrap().powi(b.unwrap().try_into().unwrap()),
-            ))),
+            (AliceVal::Float(a), AliceVal::Int(b)) => {
+                stack.push(AliceVal::Float(Some(a.unwrap().powf(b.unwrap() as f64))))
+            }
             _ => (),
         }
         Ok(())
@@ -471,66 +812,101 @@ impl Statement for ModStatement {
         stack.required_size(2)?;
         let b = stack.pop().unwrap();
         let a = stack.pop().unwrap();
-        stack.vals.push(match (a, b) {
-            (INT, INT) => INT,
-            (FLOAT, FLOAT) => FLOAT,
-            (INT, FLOAT) | (FLOAT, INT) => FLOAT,
-            _ => return Err(TypeCheckError("** only works on numbers".into())),
-        });
+        let ty = promote_numeric(a, b)
+            .ok_or_else(|| TypeCheckError::new("% only works on numbers".into()))?;
+        stack.vals.push(ty);
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         let b = stack.pop()?;
         let a = stack.pop()?;
-        // all unwrapping is safe due to type checker
-        match (a, b) {
-            (AliceVal::Int(a), AliceVal::Int(b)) => {
-                stack.push(AliceVal::Int(Some(a.unwrap() % b.unwrap())))
-            }
-            (AliceVal::Float(a), AliceVal::Float(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() % b.unwrap())))
-            }
-            (AliceVal::Int(a), AliceVal::Float(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() as f64 % b.unwrap())))
-            }
-            (AliceVal::Float(a), AliceVal::Int(b)) => {
-                stack.push(AliceVal::Float(Some(a.unwrap() % b.unwrap() as f64)))
-            }
-            _ => (),
-        }
+        let int_mod = |a: i64, b: i64| match b {
+            0 => Err("division by zero".to_string()),
+            _ => a
+                .checked_rem(b)
+                .ok_or_else(|| "integer overflow in %".to_string()),
+        };
+        stack.push(AliceVal::promote_binop(a, b, int_mod, |a, b| a % b)?);
         Ok(())
     }
 }
 
 impl Statement for ClearStatement {
+    // in interactive mode, this runs against the type stack persisting between lines,
+    // so values from previous lines are forgotten by the type checker as well
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
-        stack.required_size(0)?;
-        stack.vals.clear();
+        stack.clear();
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
-        stack.stack.clear();
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        stack.clear();
         Ok(())
     }
 }
 
 impl Statement for LetStatement {
     fn in_pattern(&self) -> StackPattern {
-        StackPattern::single(self.ty)
+        if self.literal.is_some() || self.init.is_some() {
+            StackPattern(Vec::new())
+        } else {
+            StackPattern::single(self.ty)
+        }
     }
 
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        if let Some(init) = &self.init {
+            let before = stack.vals.clone();
+            check_rc(stack, init)?;
+            let pushed_one =
+                stack.vals.len() == before.len() + 1 && stack.vals[..before.len()] == before[..];
+            if !pushed_one {
+                return Err(TypeCheckError::new(format!(
+                    "the block initializing '{}' has to push exactly one value and leave the rest of the stack alone",
+                    self.ident
+                )));
+            }
+            // unwrapping safe due to previous check
+            let ty = stack.pop().unwrap();
+            if ty != self.ty {
+                return Err(TypeCheckError::new(format!(
+                    "the block initializing '{}' pushes {} instead of {}",
+                    self.ident,
+                    type_name(ty),
+                    type_name(self.ty)
+                )));
+            }
+        }
         stack.vars.insert(self.ident.clone(), self.ty);
         Ok(())
     }
 
-    fn execute(&self, stack: &mut AliceStack, table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        if let Some(init) = &self.init {
+            for s in init {
+                s.run(stack, table, ctx)?;
+            }
+        }
         table.put(
             self.ident.clone(),
-            if self.literal.is_some() {
-                self.literal.as_ref().unwrap().clone()
+            if let Some(literal) = &self.literal {
+                literal.clone()
             } else {
                 stack.pop().unwrap()
             },
@@ -544,15 +920,35 @@ impl Statement for PushFromTableStatement {
         if let Some(ty) = stack.vars.get(&self.0) {
             stack.vals.push(*ty);
             Ok(())
-        } else {
-            Err(TypeCheckError(format!(
-                "variable binding {} doesn't exist when this executes",
+        } else if stack.funs.contains_key(&self.0) {
+            Err(TypeCheckError::new(format!(
+                "'{0}' is a function and functions can't be pushed, did you mean '{0}()'?",
                 self.0
-            )))
+            ))
+            .at(&self.1))
+        } else {
+            let candidates = stack
+                .vars
+                .keys()
+                .map(String::as_str)
+                .chain(crate::parser::WORDS.iter().copied());
+            let hint = match crate::utils::closest_match(&self.0, candidates) {
+                Some(word) => format!(", did you mean '{word}'?"),
+                None => String::new(),
+            };
+            Err(
+                TypeCheckError::new(format!("unknown word or variable '{}'{hint}", self.0))
+                    .at(&self.1),
+            )
         }
     }
 
-    fn execute(&self, stack: &mut AliceStack, table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         // unwrapping safe due to type checker
         stack.push(table.get(&self.0).unwrap().clone());
         Ok(())
@@ -561,25 +957,27 @@ impl Statement for PushFromTableStatement {
 
 impl Statement for ExecuteFunStatement {
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
-        let sig = stack.funs.remove(&self.0);
-        if sig.is_some() {
-            let sig_clone = sig.as_ref().unwrap().clone();
-            stack.funs.insert(self.0.clone(), sig.unwrap());
-            let sig = sig_clone;
+        if let Some(sig) = stack.funs.get(&self.0) {
+            let sig = sig.clone();
             sig.0.type_check(stack)?;
             if sig.1 != 0 {
                 stack.vals.push(sig.1);
             }
             Ok(())
         } else {
-            Err(TypeCheckError(format!(
+            Err(TypeCheckError::new(format!(
                 "function '{}' doesn't exist when this executes!",
                 self.0
             )))
         }
     }
 
-    fn execute(&self, stack: &mut AliceStack, table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         let fun = table.take(&self.0);
         if fun.is_none() {
             panic!("fix your type checker, dumbass!")
@@ -587,28 +985,218 @@ impl Statement for ExecuteFunStatement {
         let fun = fun.unwrap();
         let fun_clone = fun.clone();
         table.put(self.0.clone(), fun);
-        if let AliceVal::Function(Some(f)) = fun_clone {
-            f.execute(stack, table)
+        let f = if let AliceVal::Function(Some(f)) = fun_clone {
+            f
         } else {
             panic!("fix your type checker, dumbass")
+        };
+        if !f.memoized {
+            return f.execute(stack, table, ctx);
+        }
+        // unwrapping safe due to type checker
+        let argc = f.args.0.len();
+        let key = (0..argc)
+            .map(|i| crate::json::tagged(stack.get(i).unwrap()))
+            .collect::<Vec<_>>()
+            .join(",");
+        if let Some(result) = table.memo.get(&self.0).and_then(|memo| memo.get(&key)) {
+            let result = result.clone();
+            for _ in 0..argc {
+                stack.pop()?;
+            }
+            if let Some(val) = result {
+                stack.push(val);
+            }
+            return Ok(());
         }
+        f.execute(stack, table, ctx)?;
+        let result = if f.return_type != 0 {
+            stack.get(0).cloned()
+        } else {
+            None
+        };
+        table
+            .memo
+            .entry(self.0.clone())
+            .or_default()
+            .insert(key, result);
+        Ok(())
     }
 }
 
 impl Statement for FunStatement {
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        // registered before checking the body to allow recursion
         stack.funs.insert(
             self.ident.clone(),
             (self.fun.args.clone(), self.fun.return_type),
         );
-        Ok(())
+        self.fun
+            .type_check(stack)
+            .map_err(|e| e.prefix(format!("in function '{}': ", self.ident)))
     }
 
-    fn execute(&self, _stack: &mut AliceStack, table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        _stack: &mut AliceStack,
+        table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         table.put(
             self.ident.clone(),
             AliceVal::Function(Some(self.fun.clone())),
         );
+        // results of a previous definition don't apply anymore
+        table.memo.remove(&self.ident);
+        Ok(())
+    }
+}
+
+impl Statement for ObjectStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        for (member, default) in &self.0.defaults {
+            // unwrapping safe as the parser only takes defaults of declared members
+            let ty = self.0.member_type(member).unwrap();
+            if type_bit(default) != ty {
+                return Err(TypeCheckError::new(format!(
+                    "member {member} of object {} is {}, but its default {default} is {}",
+                    self.0.name,
+                    type_name(ty),
+                    type_name(type_bit(default))
+                )));
+            }
+        }
+        stack
+            .funs
+            .insert(self.0.name.clone(), (self.0.args(), self.0.type_hash));
+        stack.objects.insert(self.0.type_hash, self.0.clone());
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        _stack: &mut AliceStack,
+        table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let constructor = AliceFun::new(self.0.args(), self.0.type_hash, vec![self.0.clone()]);
+        table.put(self.0.name.clone(), AliceVal::Function(Some(constructor)));
+        table.memo.remove(&self.0.name);
+        table.objects.insert(self.0.name.clone(), self.0.clone());
+        Ok(())
+    }
+}
+
+impl Statement for ConstructStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        // the constructor function is bound under the name of the type
+        let ty = match stack.funs.get(&self.ty) {
+            Some((_, ty)) => stack.objects.get(ty).cloned(),
+            None => None,
+        };
+        let ty = ty.ok_or_else(|| {
+            TypeCheckError::new(format!(
+                "{}(...) constructs objects, but there is no object type {}",
+                self.ty, self.ty
+            ))
+        })?;
+        for (member, init) in &self.members {
+            let member_ty = ty.member_type(member).ok_or_else(|| {
+                TypeCheckError::new(format!("object {} has no member {member}", ty.name))
+            })?;
+            let before = stack.vals.clone();
+            check_rc(stack, init)?;
+            let pushed_one =
+                stack.vals.len() == before.len() + 1 && stack.vals[..before.len()] == before[..];
+            if !pushed_one {
+                return Err(TypeCheckError::new(format!(
+                    "the block initializing member {member} has to push exactly one value and leave the rest of the stack alone"
+                )));
+            }
+            // unwrapping safe due to previous check
+            let val_ty = stack.pop().unwrap();
+            if val_ty != member_ty {
+                return Err(TypeCheckError::new(format!(
+                    "member {member} of {} is {}, but is initialized with {}",
+                    ty.name,
+                    type_name(member_ty),
+                    type_name(val_ty)
+                )));
+            }
+        }
+        for (member, _) in &ty.members {
+            let given = self.members.iter().any(|(m, _)| m == member);
+            if !given && !ty.defaults.contains_key(member) {
+                return Err(TypeCheckError::new(format!(
+                    "member {member} of {} has no default, so it has to be given",
+                    ty.name
+                )));
+            }
+        }
+        stack.vals.push(ty.type_hash);
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let ty = table
+            .objects
+            .get(&self.ty)
+            .cloned()
+            .ok_or_else(|| format!("unknown object type {}", self.ty))?;
+        let mut members = ty.defaults.clone();
+        for (member, init) in &self.members {
+            for s in init {
+                s.run(stack, table, ctx)?;
+            }
+            members.insert(member.clone(), stack.pop()?);
+        }
+        stack.push(ty.instantiate(members));
+        Ok(())
+    }
+}
+
+impl Statement for MemberAccessStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(1)?;
+        let ty = stack.pop().unwrap();
+        let obj_type = match stack.objects.get(&ty) {
+            Some(obj_type) if is_object(&ty) => obj_type,
+            _ => {
+                return Err(TypeCheckError::new(format!(
+                    "member access .{} needs an object, found {}",
+                    self.0,
+                    type_name(ty)
+                )))
+            }
+        };
+        match obj_type.member_type(&self.0) {
+            Some(member) => {
+                stack.vals.push(member);
+                Ok(())
+            }
+            None => Err(TypeCheckError::new(format!(
+                "object {} has no member {}",
+                obj_type.name, self.0
+            ))),
+        }
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let member = match stack.pop()? {
+            AliceVal::Object(Some(obj)) => obj.member(&self.0).cloned(),
+            val => return Err(format!(".{} of {}", self.0, val.type_name())),
+        };
+        stack.push(member.ok_or_else(|| format!("missing member {}", self.0))?);
         Ok(())
     }
 }
@@ -618,23 +1206,29 @@ impl Statement for IfStatement {
         StackPattern::single(BOOL)
     }
 
+    // the body may not run, which is the same as an empty else body
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
-        let stack_size_0 = stack.vals.len();
-        check_rc(stack, &self.0.body)?;
-        if stack.vals.len() != stack_size_0 {
-            Err(TypeCheckError(
+        let mut body_stack = stack.clone();
+        check_rc(&mut body_stack, &self.0.body)?;
+        if body_stack.vals != stack.vals {
+            return Err(TypeCheckError::new(
                 "if without else part is not allowed to modify stack".into(),
-            ))
-        } else {
-            Ok(())
+            ));
         }
+        let skipped = stack.clone();
+        stack.merge_branches(body_stack, &skipped, "running and skipping the if body")
     }
 
-    fn execute(&self, stack: &mut AliceStack, table: &mut AliceTable) -> Result<(), String> {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         if let Ok(AliceVal::Bool(Some(b))) = stack.pop() {
             if b {
                 for s in &self.0.body {
-                    s.execute(stack, table)?;
+                    s.run(stack, table, ctx)?;
                 }
             }
         } else {
@@ -644,45 +1238,364 @@ impl Statement for IfStatement {
     }
 }
 
-impl Statement for IfElseStatement {
-    fn in_pattern(&self) -> StackPattern {
-        StackPattern::single(BOOL)
-    }
-
+impl Statement for WhileStatement {
+    // the condition pushes a bool and the body may run any number of times,
+    // so like an if without else it may not modify the stack
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
-        let mut stack_clone = stack.clone();
-        check_rc(stack, &self.0.if_body)?;
-        check_rc(&mut stack_clone, &self.0.else_body)?;
-        if stack_clone == stack {
-            Ok(())
-        } else {
-            Err(TypeCheckError(
-                "if and else body don't have equal affect on stack".into(),
-            ))
+        let mut cond_stack = stack.clone();
+        check_rc(&mut cond_stack, &self.0.cond)?;
+        if cond_stack.pop() != Some(BOOL) || cond_stack.vals != stack.vals {
+            return Err(TypeCheckError::new(
+                "while condition has to push exactly one bool".into(),
+            ));
         }
+        let mut body_stack = cond_stack.clone();
+        check_rc(&mut body_stack, &self.0.body)?;
+        if body_stack.vals != stack.vals {
+            return Err(TypeCheckError::new(
+                "while body is not allowed to modify stack".into(),
+            ));
+        }
+        stack.merge_branches(
+            body_stack,
+            &cond_stack,
+            "running and skipping the while body",
+        )
     }
 
-    fn execute(&self, stack: &mut AliceStack, table: &mut AliceTable) -> Result<(), String> {
-        if let Ok(AliceVal::Bool(Some(b))) = stack.pop() {
-            for s in if b {
-                &self.0.if_body
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        loop {
+            for s in &self.0.cond {
+                s.run(stack, table, ctx)?;
+            }
+            if let Ok(AliceVal::Bool(Some(b))) = stack.pop() {
+                if !b {
+                    return Ok(());
+                }
             } else {
-                &self.0.else_body
-            } {
-                s.execute(stack, table)?;
+                panic!("fix your type checker!")
+            }
+            for s in &self.0.body {
+                s.run(stack, table, ctx)?;
             }
-        } else {
-            panic!("fix your type checker!")
         }
-        Ok(())
     }
 }
 
-impl Statement for ReadInputStatement {
-    fn out_pattern(&self) -> StackPattern {
-        StackPattern::single(STRING)
-    }
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+impl Statement for MatchStatement {
+    // exactly one arm runs, so all arms need the same effect like the branches of if-else
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(1)?;
+        let ty = stack.pop().unwrap();
+        // the object type of each object arm, to bind its members in the body
+        let mut arm_types = Vec::with_capacity(self.0.arms.len());
+        for (i, (pattern, _)) in self.0.arms.iter().enumerate() {
+            let (arm_ty, obj_ty) = match pattern {
+                MatchPattern::Literal(val) => (type_bit(val), None),
+                MatchPattern::Object(name) => {
+                    // the constructor of the object type is bound under its name
+                    let obj_ty = match stack.funs.get(name) {
+                        Some((_, ty)) => stack.objects.get(ty).cloned(),
+                        None => None,
+                    }
+                    .ok_or_else(|| {
+                        TypeCheckError::new(format!(
+                            "match arm {name} is no object type when this executes"
+                        ))
+                    })?;
+                    (obj_ty.type_hash, Some(obj_ty))
+                }
+            };
+            // a value of unknown type may be anything
+            if ty != ANY && arm_ty != ty {
+                let name = |ty| match stack.objects.get(&ty) {
+                    Some(obj_ty) => obj_ty.name.clone(),
+                    None => type_name(ty),
+                };
+                return Err(TypeCheckError::new(format!(
+                    "match arm {} has type {} but the matched value has type {}",
+                    pattern,
+                    name(arm_ty),
+                    name(ty)
+                )));
+            }
+            if self.0.arms[..i].iter().any(|(prev, _)| prev == pattern) {
+                return Err(TypeCheckError::new(format!(
+                    "match arm {pattern} is unreachable, there already is an arm for it"
+                )));
+            }
+            arm_types.push(obj_ty);
+        }
+        let covers = |pattern: MatchPattern| self.0.arms.iter().any(|(p, _)| p == &pattern);
+        let covers_obj = arm_types
+            .iter()
+            .flatten()
+            .any(|obj_ty| obj_ty.type_hash == ty);
+        let exhaustive = self.0.default.is_some()
+            || (ty == BOOL
+                && covers(MatchPattern::Literal(AliceVal::Bool(Some(true))))
+                && covers(MatchPattern::Literal(AliceVal::Bool(Some(false)))))
+            || covers_obj;
+        if !exhaustive {
+            return Err(TypeCheckError::new(
+                "match is not exhaustive, add a default arm _ { ... }".into(),
+            ));
+        }
+        let mut merged: Option<TypeStack> = None;
+        let arms = self.0.arms.iter().zip(arm_types.iter().map(Option::as_ref));
+        for (body, obj_ty) in arms
+            .map(|((_, body), obj_ty)| (body, obj_ty))
+            .chain(self.0.default.iter().map(|body| (body, None)))
+        {
+            let mut arm_stack = stack.clone();
+            let members = obj_ty.map_or(&[][..], |obj_ty| &obj_ty.members[..]);
+            for (member, member_ty) in members {
+                arm_stack.vars.insert(member.clone(), *member_ty);
+            }
+            check_rc(&mut arm_stack, body)?;
+            // the members are only bound in the body
+            for (member, _) in members {
+                match stack.vars.get(member) {
+                    Some(ty) => arm_stack.vars.insert(member.clone(), *ty),
+                    None => arm_stack.vars.remove(member),
+                };
+            }
+            merged = Some(match merged {
+                None => arm_stack,
+                Some(prev) => {
+                    let mut both = stack.clone();
+                    both.merge_branches(prev, &arm_stack, "match arms")?;
+                    both
+                }
+            });
+        }
+        if let Some(merged) = merged {
+            *stack = merged;
+        }
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let head = stack.pop()?;
+        let arm = self
+            .0
+            .arms
+            .iter()
+            .find(|(pattern, _)| match (pattern, &head) {
+                (MatchPattern::Literal(val), _) => val.eq_promoted(&head),
+                (MatchPattern::Object(name), AliceVal::Object(Some(obj))) => table
+                    .objects
+                    .get(name)
+                    .is_some_and(|ty| ty.type_hash == obj.type_hash),
+                (MatchPattern::Object(_), _) => false,
+            });
+        let mut prev = Vec::new();
+        let body = match arm {
+            Some((pattern, body)) => {
+                if let (MatchPattern::Object(_), AliceVal::Object(Some(obj))) = (pattern, &head) {
+                    // the members are bound in the body like the variable of a for loop
+                    for (member, val) in obj.members.iter() {
+                        prev.push((member.clone(), table.put(member.clone(), val.clone())));
+                    }
+                }
+                body
+            }
+            None => self
+                .0
+                .default
+                .as_ref()
+                .ok_or_else(|| format!("no match arm for {head}"))?,
+        };
+        let result = body.iter().try_for_each(|s| s.run(stack, table, ctx));
+        for (member, val) in prev {
+            table.take(&member);
+            if let Some(val) = val {
+                table.put(member, val);
+            }
+        }
+        result
+    }
+}
+
+impl Statement for ForStatement {
+    // like while, the body may run any number of times and may not modify the stack
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        for (bound, what) in [(&self.0.start, "start"), (&self.0.end, "end")] {
+            let before = stack.vals.clone();
+            check_rc(stack, bound)?;
+            if stack.pop() != Some(INT) || stack.vals != before {
+                return Err(TypeCheckError::new(format!(
+                    "for range {what} has to push exactly one int"
+                )));
+            }
+        }
+        let prev = stack.vars.insert(self.0.var.clone(), INT);
+        let mut body_stack = stack.clone();
+        check_rc(&mut body_stack, &self.0.body)?;
+        if body_stack.vals != stack.vals {
+            return Err(TypeCheckError::new(
+                "for body is not allowed to modify stack".into(),
+            ));
+        }
+        let skipped = stack.clone();
+        stack.merge_branches(body_stack, &skipped, "running and skipping the for body")?;
+        // the loop variable is only bound in the body
+        match prev {
+            Some(ty) => stack.vars.insert(self.0.var.clone(), ty),
+            None => stack.vars.remove(&self.0.var),
+        };
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let mut bounds = [0; 2];
+        for (i, bound) in [&self.0.start, &self.0.end].into_iter().enumerate() {
+            for s in bound {
+                s.run(stack, table, ctx)?;
+            }
+            // unwrapping safe due to type checker
+            bounds[i] = stack.pop()?.unchecked_int();
+        }
+        let prev = table.take(&self.0.var);
+        for i in bounds[0]..bounds[1] {
+            table.put(self.0.var.clone(), AliceVal::Int(Some(i)));
+            for s in &self.0.body {
+                s.run(stack, table, ctx)?;
+            }
+        }
+        table.take(&self.0.var);
+        if let Some(prev) = prev {
+            table.put(self.0.var.clone(), prev);
+        }
+        Ok(())
+    }
+}
+
+impl Statement for IfElseStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern::single(BOOL)
+    }
+
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        let mut if_stack = stack.clone();
+        let mut else_stack = stack.clone();
+        check_rc(&mut if_stack, &self.0.if_body)?;
+        check_rc(&mut else_stack, &self.0.else_body)?;
+        stack.merge_branches(if_stack, &else_stack, "if and else body")
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        if let Ok(AliceVal::Bool(Some(b))) = stack.pop() {
+            for s in if b {
+                &self.0.if_body
+            } else {
+                &self.0.else_body
+            } {
+                s.run(stack, table, ctx)?;
+            }
+        } else {
+            panic!("fix your type checker!")
+        }
+        Ok(())
+    }
+}
+
+impl Statement for TryCatchStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        let mut body_stack = stack.clone();
+        check_rc(&mut body_stack, &self.0.body)?;
+        // the body may fail before binding anything, so the handler can't rely on its bindings
+        let mut handler_stack = stack.clone();
+        handler_stack.vals.push(STRING);
+        check_rc(&mut handler_stack, &self.0.handler)?;
+        stack.merge_branches(body_stack, &handler_stack, "try and catch body")
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let snapshot = stack.snapshot();
+        for s in &self.0.body {
+            if let Err(e) = s.run(stack, table, ctx) {
+                stack.restore(snapshot);
+                stack.push(AliceVal::String(Some(e)));
+                for s in &self.0.handler {
+                    s.run(stack, table, ctx)?;
+                }
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Statement for InterpolateStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        for part in &self.0 {
+            if let StringPart::Var(ident) = part {
+                if !stack.vars.contains_key(ident) {
+                    return Err(TypeCheckError::new(format!(
+                        "interpolation '${{{ident}}}': variable binding {ident} doesn't exist when this executes"
+                    )));
+                }
+            }
+        }
+        stack.vals.push(STRING);
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let mut s = String::new();
+        for part in &self.0 {
+            match part {
+                StringPart::Literal(lit) => s.push_str(lit),
+                // unwrapping safe due to type checker
+                StringPart::Var(ident) => s.push_str(&table.get(ident).unwrap().to_string()),
+            }
+        }
+        stack.push(AliceVal::String(Some(s)));
+        Ok(())
+    }
+}
+
+impl Statement for ReadInputStatement {
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(STRING)
+    }
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
         use std::io;
         let mut s = String::new();
         io::stdin().read_line(&mut s).map_err(|e| e.to_string())?;
@@ -695,23 +1608,132 @@ impl Statement for ReadInputStatement {
     }
 }
 
+/// the type check shared by == and !=
+fn check_eq_types(stack: &mut TypeStack, op: &str) -> Result<(), TypeCheckError> {
+    stack.required_size(2)?;
+    let b = stack.pop().unwrap();
+    let a = stack.pop().unwrap();
+    // ints and floats are compared by value, e. g. 2 2.0 == is true
+    if a != b && !matches!((a, b), (INT, FLOAT) | (FLOAT, INT)) {
+        Err(TypeCheckError::new(format!(
+            "cannot {op} compare values of different types ({} and {})",
+            type_name(a),
+            type_name(b)
+        )))
+    } else {
+        stack.vals.push(BOOL);
+        Ok(())
+    }
+}
+
 impl Statement for EqsStatement {
     fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
-        stack.required_size(2)?;
-        let a = stack.pop().unwrap();
-        let b = stack.pop().unwrap();
-        if a != b {
-            Err(TypeCheckError("cannot == compare values of different types".into()))
-        } else {
-            stack.vals.push(BOOL);
-            Ok(())
+        check_eq_types(stack, "==")
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let b = stack.pop()?;
+        let a = stack.pop()?;
+        stack.push(AliceVal::Bool(Some(a.eq_promoted(&b))));
+        Ok(())
+    }
+}
+
+impl Statement for NotStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern::single(BOOL)
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(BOOL)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        // unwrapping safe due to type checker
+        let b = stack.pop()?.unchecked_bool();
+        stack.push(AliceVal::Bool(Some(!b)));
+        Ok(())
+    }
+}
+
+/// the type check shared by and and or
+fn check_bool_operands(stack: &mut TypeStack, op: &str) -> Result<(), TypeCheckError> {
+    stack.required_size(2)?;
+    for _ in 0..2 {
+        let ty = stack.pop().unwrap();
+        if ty != BOOL {
+            return Err(TypeCheckError::new(format!(
+                "{op} requires two bools, found {}",
+                type_name(ty)
+            )));
         }
     }
+    stack.vals.push(BOOL);
+    Ok(())
+}
+
+impl Statement for AndStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        check_bool_operands(stack, "and")
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        // unwrapping safe due to type checker
+        let b = stack.pop()?.unchecked_bool();
+        let a = stack.pop()?.unchecked_bool();
+        stack.push(AliceVal::Bool(Some(a && b)));
+        Ok(())
+    }
+}
+
+impl Statement for OrStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        check_bool_operands(stack, "or")
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        // unwrapping safe due to type checker
+        let b = stack.pop()?.unchecked_bool();
+        let a = stack.pop()?.unchecked_bool();
+        stack.push(AliceVal::Bool(Some(a || b)));
+        Ok(())
+    }
+}
+
+impl Statement for NotEqsStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        check_eq_types(stack, "!=")
+    }
 
-    fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
-        let a = stack.pop();
-        let b = stack.pop();
-        stack.push(AliceVal::Bool(Some(a == b)));
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let b = stack.pop()?;
+        let a = stack.pop()?;
+        stack.push(AliceVal::Bool(Some(!a.eq_promoted(&b))));
         Ok(())
     }
 }
@@ -723,24 +1745,31 @@ macro_rules! cmp_statement {
             fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
                 stack.required_size(2)?;
                 // unwrapping safe due to above check
-                let a = stack.pop().unwrap();
                 let b = stack.pop().unwrap();
-                if !(a == FLOAT || a == INT && a == b) {
-                    Err(TypeCheckError("can only arithmetically compare int to int and float to float!".into()))
+                let a = stack.pop().unwrap();
+                // ints and floats are compared by value like with ==, but lists aren't ordered
+                if !matches!(promote_numeric(a, b), Some(INT | FLOAT)) {
+                    Err(TypeCheckError::new(format!(
+                        "can only arithmetically compare numbers, found {} and {}",
+                        type_name(a),
+                        type_name(b)
+                    )))
                 } else {
                     stack.vals.push(BOOL);
                     Ok(())
                 }
             }
 
-            fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable) -> Result<(), String> {
+            fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable, _ctx: &mut AliceContext) -> Result<(), String> {
                 // unwrapping safe due to type checker
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
                 stack.push(AliceVal::Bool(Some(
                     match (a, b) {
-                        (AliceVal::Float(f1), AliceVal::Float(f2)) => f1 $op f2,
-                        (AliceVal::Int(n1), AliceVal::Int(n2)) => n1 $op n2,
+                        (AliceVal::Float(Some(f1)), AliceVal::Float(Some(f2))) => f1 $op f2,
+                        (AliceVal::Int(Some(n1)), AliceVal::Int(Some(n2))) => n1 $op n2,
+                        (AliceVal::Int(Some(n)), AliceVal::Float(Some(f))) => (n as f64) $op f,
+                        (AliceVal::Float(Some(f)), AliceVal::Int(Some(n))) => f $op (n as f64),
                         _ => panic!("fix your type checker, dumbass"),
                     }
                 )));
@@ -755,3 +1784,990 @@ cmp_statement![GtEqsStatement, >=];
 cmp_statement![LtStatement, <];
 cmp_statement![LtEqsStatement, <=];
 
+macro_rules! parity_statement {
+    ($name:ident, $rem:literal) => {
+        impl Statement for $name {
+            fn in_pattern(&self) -> StackPattern {
+                StackPattern::single(INT)
+            }
+
+            fn out_pattern(&self) -> StackPattern {
+                StackPattern::single(BOOL)
+            }
+
+            fn execute(
+                &self,
+                stack: &mut AliceStack,
+                _table: &mut AliceTable,
+                _ctx: &mut AliceContext,
+            ) -> Result<(), String> {
+                // unwrapping safe due to type checker
+                let n = stack.pop()?.unchecked_int();
+                // rem_euclid so that negative odd numbers are odd too
+                stack.push(AliceVal::Bool(Some(n.rem_euclid(2) == $rem)));
+                Ok(())
+            }
+        }
+    };
+}
+
+parity_statement![IsEvenStatement, 0];
+parity_statement![IsOddStatement, 1];
+
+macro_rules! sign_statement {
+    ($name:ident, $op:tt) => {
+        impl Statement for $name {
+            fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+                stack.required_size(1)?;
+                // unwrapping safe due to above check
+                let ty = stack.pop().unwrap();
+                if !matches!(ty, INT | FLOAT) {
+                    Err(TypeCheckError::new(format!(
+                        "can only check the sign of numbers, found {}",
+                        type_name(ty)
+                    )))
+                } else {
+                    stack.vals.push(BOOL);
+                    Ok(())
+                }
+            }
+
+            fn execute(&self, stack: &mut AliceStack, _table: &mut AliceTable, _ctx: &mut AliceContext) -> Result<(), String> {
+                let result = match stack.pop()? {
+                    AliceVal::Int(Some(n)) => n $op 0,
+                    // false for NaN
+                    AliceVal::Float(Some(f)) => f $op 0.0,
+                    _ => panic!("fix your type checker, dumbass"),
+                };
+                stack.push(AliceVal::Bool(Some(result)));
+                Ok(())
+            }
+        }
+    }
+}
+
+sign_statement![IsPositiveStatement, >];
+sign_statement![IsNegativeStatement, <];
+sign_statement![IsZeroStatement, ==];
+
+impl Statement for ArgsStatement {
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(list_of(STRING))
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        stack.push(AliceVal::list(
+            STRING,
+            ctx.args
+                .iter()
+                .map(|a| AliceVal::String(Some(a.clone())))
+                .collect(),
+        ));
+        Ok(())
+    }
+}
+
+impl Statement for ReadFileStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern::single(STRING)
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(STRING)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        ctx.require_io("readfile")?;
+        // unwrapping safe due to type checker
+        let path = stack.pop()?.unchecked_string();
+        let content = ctx.fs.read(&path)?;
+        stack.push(AliceVal::String(Some(content)));
+        Ok(())
+    }
+}
+
+impl Statement for WriteFileStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern(vec![STRING, STRING])
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        ctx.require_io("writefile")?;
+        // unwrapping safe due to type checker
+        let content = stack.pop()?.unchecked_string();
+        let path = stack.pop()?.unchecked_string();
+        ctx.fs.write(&path, &content)
+    }
+}
+
+impl Statement for RandStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        // int form (n rand -> int) leaves the type stack as is
+        if stack.vals.last() != Some(&INT) {
+            stack.vals.push(FLOAT);
+        }
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        if let Some(AliceVal::Int(Some(n))) = stack.get(0) {
+            let n = *n;
+            if n <= 0 {
+                return Err(format!("rand bound must be positive, got {n}"));
+            }
+            stack.pop()?;
+            stack.push(AliceVal::Int(Some((ctx.rng.next_u64() % n as u64) as i64)));
+        } else {
+            stack.push(AliceVal::Float(Some(ctx.rng.next_f64())));
+        }
+        Ok(())
+    }
+}
+
+impl Statement for NowStatement {
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(INT)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        stack.push(AliceVal::Int(Some(ctx.clock.now_millis())));
+        Ok(())
+    }
+}
+
+impl Statement for SleepStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern::single(INT)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        // unwrapping safe due to type checker
+        let millis = stack.pop()?.unchecked_int();
+        if millis < 0 {
+            return Err(format!(
+                "cannot sleep for a negative duration ({millis} ms)"
+            ));
+        }
+        ctx.clock.sleep_millis(millis as u64);
+        Ok(())
+    }
+}
+
+impl Statement for ConvertStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(1)?;
+        let from = stack.pop().unwrap();
+        let to = type_bit(&self.0);
+        if convertible(from, to) {
+            stack.vals.push(to);
+            Ok(())
+        } else {
+            Err(TypeCheckError::new(format!(
+                "cannot convert {} to {}",
+                type_name(from),
+                self.0.type_name()
+            )))
+        }
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let val = stack.pop()?;
+        stack.push(val.convert(&self.0)?);
+        Ok(())
+    }
+}
+
+impl Statement for DumpJsonStatement {
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        println!("{}", crate::json::tagged_stack(stack));
+        Ok(())
+    }
+}
+
+impl Statement for AbortStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern(vec![INT, STRING])
+    }
+
+    fn diverges(&self) -> bool {
+        true
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        // unwrapping safe due to type checker
+        let code = stack.pop()?.unchecked_int();
+        let msg = stack.pop()?.unchecked_string();
+        eprintln!("{msg}");
+        std::process::exit(code as i32);
+    }
+}
+
+impl Statement for LenStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(1)?;
+        let ty = stack.pop().unwrap();
+        if ty != STRING && !is_list(&ty) {
+            return Err(TypeCheckError::new(format!(
+                "len only works on strings and lists, found {}",
+                type_name(ty)
+            )));
+        }
+        stack.vals.push(INT);
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let len = match stack.pop()? {
+            AliceVal::String(Some(s)) => s.chars().count(),
+            AliceVal::List(Some(list)) => list.vals.len(),
+            val => return Err(format!("len of {}", val.type_name())),
+        };
+        stack.push(AliceVal::Int(Some(len as i64)));
+        Ok(())
+    }
+}
+
+impl Statement for ByteLenStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern::single(STRING)
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(INT)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        // unwrapping safe due to type checker
+        let len = stack.pop()?.unchecked_string().len();
+        stack.push(AliceVal::Int(Some(len as i64)));
+        Ok(())
+    }
+}
+
+impl Statement for ToJsonStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern::single(ANY)
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(STRING)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let val = stack.pop()?;
+        stack.push(AliceVal::String(Some(crate::json::plain(&val))));
+        Ok(())
+    }
+}
+
+impl Statement for FromJsonStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern::single(STRING)
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(type_bit(&self.0))
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        // unwrapping safe due to type checker
+        let json = stack.pop()?.unchecked_string();
+        stack.push(crate::json::parse_as(&json, &self.0)?);
+        Ok(())
+    }
+}
+
+impl Statement for FromJsonObjectStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        // the constructor of the object type is bound under its name
+        let ty = match stack.funs.get(&self.0) {
+            Some((_, ty)) if stack.objects.contains_key(ty) => *ty,
+            _ => {
+                return Err(TypeCheckError::new(format!(
+                    "unknown object type {} when this executes",
+                    self.0
+                )))
+            }
+        };
+        StackPattern::single(STRING).type_check(stack)?;
+        stack.vals.push(ty);
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let ty = table
+            .objects
+            .get(&self.0)
+            .cloned()
+            .ok_or_else(|| format!("unknown object type {}", self.0))?;
+        // unwrapping safe due to type checker
+        let json = stack.pop()?.unchecked_string();
+        stack.push(crate::json::parse_object(&json, &ty, &table.objects)?);
+        Ok(())
+    }
+}
+
+impl Statement for ListStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        // the body sees the bindings, but not the values, of the enclosing stack
+        let mut body_stack = stack.scope();
+        check_rc(&mut body_stack, &self.0)?;
+        let elem = match body_stack.vals.first() {
+            Some(elem) => *elem,
+            None => {
+                return Err(TypeCheckError::new(
+                    "cannot infer the element type of an empty list".into(),
+                ))
+            }
+        };
+        if let Some(other) = body_stack.vals.iter().find(|ty| **ty != elem) {
+            return Err(TypeCheckError::new(format!(
+                "list elements must all have the same type, found {} and {}",
+                type_name(elem),
+                type_name(*other)
+            )));
+        }
+        stack.vals.push(list_of(elem));
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        table: &mut AliceTable,
+        ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let height = stack.size();
+        for s in &self.0 {
+            s.run(stack, table, ctx)?;
+        }
+        let mut vals = Vec::with_capacity(stack.size() - height);
+        while stack.size() > height {
+            vals.push(stack.pop()?);
+        }
+        vals.reverse();
+        // unwrapping safe due to type checker, which doesn't allow empty lists
+        let elem_type = type_bit(vals.first().unwrap());
+        stack.push(AliceVal::list(elem_type, vals));
+        Ok(())
+    }
+}
+
+impl Statement for ClampStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(3)?;
+        let hi = stack.pop().unwrap();
+        let lo = stack.pop().unwrap();
+        let val = stack.pop().unwrap();
+        let ty = promote_numeric(val, lo)
+            .and_then(|ty| promote_numeric(ty, hi))
+            .filter(|ty| matches!(*ty, INT | FLOAT))
+            .ok_or_else(|| TypeCheckError::new("clamp only works on numbers".into()))?;
+        stack.vals.push(ty);
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let hi = stack.pop()?;
+        let lo = stack.pop()?;
+        let val = stack.pop()?;
+        // all unwrapping is safe due to type checker
+        stack.push(match (val, lo, hi) {
+            (AliceVal::Int(Some(val)), AliceVal::Int(Some(lo)), AliceVal::Int(Some(hi))) => {
+                if lo > hi {
+                    return Err(format!("clamp: lower bound {lo} is above upper bound {hi}"));
+                }
+                AliceVal::Int(Some(val.clamp(lo, hi)))
+            }
+            (val, lo, hi) => {
+                let [val, lo, hi] = [val, lo, hi].map(|v| v.convert(&AliceVal::float()));
+                let (val, lo, hi) = (
+                    val?.unchecked_float(),
+                    lo?.unchecked_float(),
+                    hi?.unchecked_float(),
+                );
+                if lo.is_nan() || hi.is_nan() {
+                    return Err("clamp: bounds must not be NaN".into());
+                }
+                if lo > hi {
+                    return Err(format!("clamp: lower bound {lo} is above upper bound {hi}"));
+                }
+                AliceVal::Float(Some(val.clamp(lo, hi)))
+            }
+        });
+        Ok(())
+    }
+}
+
+impl Statement for FieldsStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(1)?;
+        let ty = stack.pop().unwrap();
+        if !is_object(&ty) {
+            return Err(TypeCheckError::new(format!(
+                "fields only works on objects, found {}",
+                type_name(ty)
+            )));
+        }
+        stack.vals.push(list_of(STRING));
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let obj = match stack.pop()? {
+            AliceVal::Object(Some(obj)) => obj,
+            val => return Err(format!("fields of {}", val.type_name())),
+        };
+        let mut names: Vec<&String> = obj.members.keys().collect();
+        names.sort();
+        let names = names
+            .into_iter()
+            .map(|name| AliceVal::String(Some(name.clone())))
+            .collect();
+        stack.push(AliceVal::list(STRING, names));
+        Ok(())
+    }
+}
+
+impl Statement for ReverseStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(1)?;
+        let ty = stack.pop().unwrap();
+        if ty != STRING && !is_list(&ty) {
+            return Err(TypeCheckError::new(format!(
+                "reverse only works on strings and lists, found {}",
+                type_name(ty)
+            )));
+        }
+        stack.vals.push(ty);
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let reversed = match stack.pop()? {
+            AliceVal::String(Some(s)) => AliceVal::String(Some(s.chars().rev().collect())),
+            AliceVal::List(Some(mut list)) => {
+                list.vals.reverse();
+                AliceVal::List(Some(list))
+            }
+            val => return Err(format!("reverse of {}", val.type_name())),
+        };
+        stack.push(reversed);
+        Ok(())
+    }
+}
+
+impl Statement for ContainsStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(2)?;
+        let val = stack.pop().unwrap();
+        let list = stack.pop().unwrap();
+        if !is_list(&list) {
+            return Err(TypeCheckError::new(format!(
+                "contains only works on lists, found {}",
+                type_name(list)
+            )));
+        }
+        if list_elem(list) != val {
+            return Err(TypeCheckError::new(format!(
+                "{} can never contain a {}",
+                type_name(list),
+                type_name(val)
+            )));
+        }
+        stack.vals.push(BOOL);
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let val = stack.pop()?;
+        let contains = match stack.pop()? {
+            AliceVal::List(Some(list)) => list.vals.iter().any(|v| v.eq_promoted(&val)),
+            list => return Err(format!("contains of {}", list.type_name())),
+        };
+        stack.push(AliceVal::Bool(Some(contains)));
+        Ok(())
+    }
+}
+
+impl Statement for IndexOfStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern(vec![STRING, STRING])
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(INT)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        // unwrapping safe due to type checker
+        let needle = stack.pop()?.unchecked_string();
+        let haystack = stack.pop()?.unchecked_string();
+        let index = match haystack.find(&needle) {
+            // the byte index is converted into a char index
+            Some(byte) => haystack[..byte].chars().count() as i64,
+            None => -1,
+        };
+        stack.push(AliceVal::Int(Some(index)));
+        Ok(())
+    }
+}
+
+impl Statement for ReplaceStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern(vec![STRING, STRING, STRING])
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(STRING)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        // unwrapping safe due to type checker
+        let replacement = stack.pop()?.unchecked_string();
+        let pattern = stack.pop()?.unchecked_string();
+        let src = stack.pop()?.unchecked_string();
+        if pattern.is_empty() {
+            return Err("cannot replace an empty pattern".into());
+        }
+        stack.push(AliceVal::String(Some(src.replace(&pattern, &replacement))));
+        Ok(())
+    }
+}
+
+/// pops the precision and float operands of tofixed and toscientific
+fn pop_precision_and_float(stack: &mut AliceStack, word: &str) -> Result<(usize, f64), String> {
+    // unwrapping safe due to type checker
+    let precision = stack.pop()?.unchecked_int();
+    let f = stack.pop()?.unchecked_float();
+    let precision = usize::try_from(precision)
+        .map_err(|_| format!("{word} needs a non-negative precision, found {precision}"))?;
+    Ok((precision, f))
+}
+
+impl Statement for ToFixedStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern(vec![INT, FLOAT])
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(STRING)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let (precision, f) = pop_precision_and_float(stack, "tofixed")?;
+        stack.push(AliceVal::String(Some(format!("{f:.precision$}"))));
+        Ok(())
+    }
+}
+
+impl Statement for ToScientificStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern(vec![INT, FLOAT])
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(STRING)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let (precision, f) = pop_precision_and_float(stack, "toscientific")?;
+        stack.push(AliceVal::String(Some(format!("{f:.precision$e}"))));
+        Ok(())
+    }
+}
+
+impl Statement for ZipStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(2)?;
+        let b = stack.pop().unwrap();
+        let a = stack.pop().unwrap();
+        if !is_list(&a) || !is_list(&b) {
+            return Err(TypeCheckError::new(format!(
+                "zip only works on two lists, found {} and {}",
+                type_name(a),
+                type_name(b)
+            )));
+        }
+        let pair = pair_type(list_elem(a), list_elem(b));
+        stack.vals.push(list_of(pair.type_hash));
+        stack.objects.insert(pair.type_hash, Rc::new(pair));
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let (b, a) = match (stack.pop()?, stack.pop()?) {
+            (AliceVal::List(Some(b)), AliceVal::List(Some(a))) => (b, a),
+            (b, a) => return Err(format!("zip of {} and {}", a.type_name(), b.type_name())),
+        };
+        let ty = pair_type(a.elem_type, b.elem_type);
+        let pairs = a
+            .vals
+            .into_iter()
+            .zip(b.vals)
+            .map(|(x, y)| pair(&ty, x, y))
+            .collect();
+        stack.push(AliceVal::list(ty.type_hash, pairs));
+        Ok(())
+    }
+}
+
+impl Statement for EnumerateStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(1)?;
+        let ty = stack.pop().unwrap();
+        if !is_list(&ty) {
+            return Err(TypeCheckError::new(format!(
+                "enumerate only works on lists, found {}",
+                type_name(ty)
+            )));
+        }
+        let pair = pair_type(INT, list_elem(ty));
+        stack.vals.push(list_of(pair.type_hash));
+        stack.objects.insert(pair.type_hash, Rc::new(pair));
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let list = match stack.pop()? {
+            AliceVal::List(Some(list)) => list,
+            val => return Err(format!("enumerate of {}", val.type_name())),
+        };
+        let ty = pair_type(INT, list.elem_type);
+        let pairs = list
+            .vals
+            .into_iter()
+            .enumerate()
+            .map(|(i, val)| pair(&ty, AliceVal::Int(Some(i as i64)), val))
+            .collect();
+        stack.push(AliceVal::list(ty.type_hash, pairs));
+        Ok(())
+    }
+}
+
+impl Statement for CountStatement {
+    fn in_pattern(&self) -> StackPattern {
+        StackPattern::single(list_of(BOOL))
+    }
+
+    fn out_pattern(&self) -> StackPattern {
+        StackPattern::single(INT)
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let count = match stack.pop()? {
+            AliceVal::List(Some(list)) => list.vals.iter().filter(|b| b.unchecked_bool()).count(),
+            val => return Err(format!("count of {}", val.type_name())),
+        };
+        stack.push(AliceVal::Int(Some(count as i64)));
+        Ok(())
+    }
+}
+
+impl Statement for AssertStackStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(1)?;
+        let ty = stack.pop().unwrap();
+        if !is_list(&ty) {
+            return Err(TypeCheckError::new(format!(
+                "assertstack expects a list of the expected values, found {}",
+                type_name(ty)
+            )));
+        }
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        let expected = match stack.pop()? {
+            AliceVal::List(Some(list)) => list.vals,
+            val => return Err(format!("assertstack of {}", val.type_name())),
+        };
+        if stack.equals_values(&expected) {
+            return Ok(());
+        }
+        let mut diff = format!(
+            "stack assertion failed: expected {} values, found {}",
+            expected.len(),
+            stack.size()
+        );
+        let show = |val: Option<&AliceVal>| match val {
+            Some(val) => format!("{val} ({})", val.type_name()),
+            None => "nothing".to_string(),
+        };
+        for i in 0..expected.len().max(stack.size()) {
+            let (exp, act) = (expected.get(i), stack.stack.get(i));
+            let equal = matches!((exp, act), (Some(exp), Some(act)) if exp.eq_promoted(act));
+            if !equal {
+                diff.push_str(&format!(
+                    "\n  at {i}: expected {}, found {}",
+                    show(exp),
+                    show(act)
+                ));
+            }
+        }
+        Err(diff)
+    }
+}
+
+impl Statement for SliceStatement {
+    fn custom_type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
+        stack.required_size(3)?;
+        StackPattern(vec![INT, INT]).type_check(stack)?;
+        let ty = stack.pop().unwrap();
+        if !is_list(&ty) {
+            return Err(TypeCheckError::new(format!(
+                "slice only works on lists, found {}",
+                type_name(ty)
+            )));
+        }
+        stack.vals.push(ty);
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        stack: &mut AliceStack,
+        _table: &mut AliceTable,
+        _ctx: &mut AliceContext,
+    ) -> Result<(), String> {
+        // unwrapping safe due to type checker
+        let end = stack.pop()?.unchecked_int();
+        let start = stack.pop()?.unchecked_int();
+        let mut list = match stack.pop()? {
+            AliceVal::List(Some(list)) => list,
+            val => return Err(format!("slice of {}", val.type_name())),
+        };
+        let len = list.vals.len();
+        if start < 0 || start > end || end as usize > len {
+            return Err(format!(
+                "slice {start}..{end} out of bounds for list of length {len}"
+            ));
+        }
+        list.vals.truncate(end as usize);
+        list.vals.drain(..start as usize);
+        stack.push(AliceVal::List(Some(list)));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// a clock that always reads the same time and records sleeps instead of sleeping
+    #[derive(Debug)]
+    struct MockClock {
+        now: i64,
+        slept: Rc<Cell<u64>>,
+    }
+
+    impl AliceClock for MockClock {
+        fn now_millis(&self) -> i64 {
+            self.now
+        }
+
+        fn sleep_millis(&mut self, millis: u64) {
+            self.slept.set(self.slept.get() + millis);
+        }
+    }
+
+    /// a context with a mocked clock and the cell its sleeps are recorded in
+    fn mock_ctx(now: i64) -> (AliceContext, Rc<Cell<u64>>) {
+        let slept = Rc::new(Cell::new(0));
+        let ctx = AliceContext {
+            clock: Box::new(MockClock {
+                now,
+                slept: Rc::clone(&slept),
+            }),
+            ..AliceContext::default()
+        };
+        (ctx, slept)
+    }
+
+    #[test]
+    fn now_pushes_the_clock_time() {
+        let (mut ctx, _) = mock_ctx(1_700_000_000_000);
+        let mut stack = AliceStack::default();
+        NowStatement
+            .execute(&mut stack, &mut AliceTable::default(), &mut ctx)
+            .unwrap();
+        assert_eq!(stack.stack, vec![AliceVal::Int(Some(1_700_000_000_000))]);
+    }
+
+    #[test]
+    fn sleep_asks_the_clock_for_the_duration() {
+        let (mut ctx, slept) = mock_ctx(0);
+        let mut stack = AliceStack::default();
+        stack.push(AliceVal::Int(Some(250)));
+        SleepStatement
+            .execute(&mut stack, &mut AliceTable::default(), &mut ctx)
+            .unwrap();
+        assert_eq!(slept.get(), 250);
+        assert!(stack.stack.is_empty());
+    }
+
+    #[test]
+    fn duplicating_an_object_shares_its_members() {
+        let ty = AliceObjType::new("Point".into(), vec![("x".into(), INT)]);
+        let point = ty.instantiate(std::collections::HashMap::from([(
+            "x".into(),
+            AliceVal::Int(Some(1)),
+        )]));
+        let mut stack = AliceStack::default();
+        stack.push(point.clone());
+        DupStatement
+            .execute(
+                &mut stack,
+                &mut AliceTable::default(),
+                &mut AliceContext::default(),
+            )
+            .unwrap();
+        OverStatement
+            .execute(
+                &mut stack,
+                &mut AliceTable::default(),
+                &mut AliceContext::default(),
+            )
+            .unwrap();
+        let AliceVal::Object(Some(original)) = &point else {
+            unreachable!()
+        };
+        for copy in &stack.stack {
+            let AliceVal::Object(Some(o)) = copy else {
+                unreachable!()
+            };
+            assert!(std::rc::Rc::ptr_eq(&o.members, &original.members));
+        }
+    }
+}
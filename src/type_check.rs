@@ -1,6 +1,10 @@
-use crate::runtime::AliceVal;
+use crate::error::{AliceError, Stage};
+use crate::loc::Loc;
+use crate::object::AliceObjType;
+use crate::runtime::*;
 use crate::statement::Statement;
 
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -8,66 +12,211 @@ pub const STRING: u32 = 1;
 pub const BOOL: u32 = 2;
 pub const INT: u32 = 4;
 pub const FLOAT: u32 = 8;
+pub const CHAR: u32 = 64;
 // an object ist represented by
 pub const OBJECT: u32 = 16;
-pub const OBJECT_SIG_MASK: u32 = 0b11111111111111111111111111100000;
-pub const ANY: u32 = 0b11111;
+// a list is represented by the LIST bit and the index of its element type, see list_of
+pub const LIST: u32 = 32;
+pub const LIST_ELEM_SHIFT: u32 = 8;
+pub const OBJECT_SIG_MASK: u32 = 0b11111111111111111111111100000000;
+pub const ANY: u32 = u32::MAX;
 
 #[derive(Debug, Clone)]
 pub struct TypeStack {
     pub vals: Vec<u32>,
     pub vars: HashMap<String, u32>,
     pub funs: HashMap<String, (StackPattern, u32)>,
+    /// declared object types by type bits, see ObjectStatement
+    pub objects: HashMap<u32, Rc<AliceObjType>>,
+    /// if true, unreachable code is an error instead of a warning
+    pub strict: bool,
+    /// problems that don't stop the program from running, shared by all scopes and
+    /// branches checked from the same stack
+    pub warnings: Rc<RefCell<Vec<String>>>,
+    /// the lowest height of vals since this was last reset, i. e. the values below it
+    /// weren't touched since. used to find out which statement left which value
+    pub low: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct StackPattern(pub Vec<u32>);
 
+// ANY has all bits set, but is neither an object nor a list type
 pub fn is_object(bits: &u32) -> bool {
-    bits > &15
+    *bits != ANY && bits & OBJECT != 0 && !is_list(bits)
 }
 
-pub fn check(statements: &Vec<Box<dyn Statement>>) -> Result<(), TypeCheckError> {
-    let mut stack = TypeStack::new();
-    for s in statements {
-        //println!("type stack before: {:?}", stack);
-        s.in_pattern().type_check(&mut stack)?;
-        s.custom_type_check(&mut stack)?;
-        s.out_pattern().push(&mut stack);
-        //println!("type stack after: {:?}\n", stack);
-    }
-    if stack.vals.is_empty() {
-        Ok(())
+pub fn is_list(bits: &u32) -> bool {
+    *bits != ANY && bits & LIST != 0
+}
+
+thread_local! {
+    /// element types of all list types so far, the index of each identifies its list type
+    static LIST_ELEMS: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
+}
+
+/// type bits of a list with the given element type. element types are interned, so the
+/// bits of nested lists and lists of objects fit as well: they are the LIST bit plus the
+/// (one based) index of the element type shifted by LIST_ELEM_SHIFT
+pub fn list_of(elem: u32) -> u32 {
+    LIST_ELEMS.with(|elems| {
+        let mut elems = elems.borrow_mut();
+        let index = match elems.iter().position(|e| *e == elem) {
+            Some(index) => index,
+            None => {
+                elems.push(elem);
+                elems.len() - 1
+            }
+        };
+        LIST | ((index as u32 + 1) << LIST_ELEM_SHIFT)
+    })
+}
+
+/// element type bits of the given list type bits, 0 for the bare LIST of an unknown list
+pub fn list_elem(bits: u32) -> u32 {
+    match bits >> LIST_ELEM_SHIFT {
+        0 => 0,
+        index => LIST_ELEMS.with(|elems| elems.borrow()[index as usize - 1]),
+    }
+}
+
+/// whether a value of type `actual` is accepted where `expected` is required. simple types
+/// are single bits and patterns may combine them, but list and object types only match
+/// themselves, as their upper bits are an index or a hash
+pub fn matches_type(actual: u32, expected: u32) -> bool {
+    if expected == ANY || actual == expected {
+        true
+    } else if is_list(&actual) || is_list(&expected) || actual & OBJECT != 0 {
+        false
     } else {
-        Err(TypeCheckError(format!(
-            "{} excess values on the stack!",
-            stack.vals.len()
-        )))
+        actual & expected == actual
+    }
+}
+
+/// the result type of an arithmetic operation on the given operand types;
+/// mixing int and float promotes to float and two lists are operated on element-wise.
+/// None if an operand is not a number or list of numbers
+pub fn promote_numeric(a: u32, b: u32) -> Option<u32> {
+    match (a, b) {
+        (INT, INT) => Some(INT),
+        (FLOAT, FLOAT) | (INT, FLOAT) | (FLOAT, INT) => Some(FLOAT),
+        (a, b) if is_list(&a) && is_list(&b) => {
+            promote_numeric(list_elem(a), list_elem(b)).map(list_of)
+        }
+        _ => None,
+    }
+}
+
+/// whether AliceVal::convert can convert values of type `from` to type `to`, for @ conversions
+pub fn convertible(from: u32, to: u32) -> bool {
+    matches!(
+        (from, to),
+        (_, STRING)
+            | (INT | FLOAT | STRING, INT | FLOAT)
+            | (BOOL, INT)
+            | (STRING | CHAR | INT, CHAR)
+            | (CHAR, INT)
+    )
+}
+
+/// applies the type effect of a single statement
+pub fn check_statement(stack: &mut TypeStack, s: &dyn Statement) -> Result<(), TypeCheckError> {
+    s.in_pattern().type_check(stack)?;
+    s.custom_type_check(stack)?;
+    s.out_pattern().push(stack);
+    Ok(())
+}
+
+/// checks a sequence of statements at the same level using `check_one`, usually
+/// check_statement, returning whether it diverges. statements after one that diverges
+/// never run, so they are not checked but reported as unreachable
+fn check_seq<'a>(
+    stack: &mut TypeStack,
+    mut statements: impl Iterator<Item = &'a dyn Statement>,
+    mut check_one: impl FnMut(&mut TypeStack, &dyn Statement) -> Result<(), TypeCheckError>,
+) -> Result<bool, TypeCheckError> {
+    while let Some(s) = statements.next() {
+        check_one(stack, s)?;
+        if s.diverges() {
+            if statements.next().is_some() {
+                let word = s.kind().trim_end_matches("Statement").to_lowercase();
+                let msg = format!("unreachable code: the statements after {word} never run");
+                if stack.strict {
+                    return Err(TypeCheckError::new(msg));
+                }
+                stack.warnings.borrow_mut().push(msg);
+            }
+            return Ok(true);
+        }
     }
+    Ok(false)
+}
+
+/// checks a whole program, returning the warnings.
+/// `locs` are the locations of the statements, to point at values left on the stack
+pub fn check(
+    statements: &[Box<dyn Statement>],
+    locs: &[Loc],
+    strict: bool,
+) -> Result<Vec<String>, TypeCheckError> {
+    let mut stack = TypeStack::new();
+    stack.strict = strict;
+    // the index of the statement that left each value
+    let mut origins: Vec<usize> = Vec::new();
+    let mut i = 0;
+    let diverges = check_seq(
+        &mut stack,
+        statements.iter().map(|s| s.as_ref()),
+        |stack, s| {
+            stack.low = stack.vals.len();
+            check_statement(stack, s)?;
+            origins.truncate(stack.low);
+            origins.resize(stack.vals.len(), i);
+            i += 1;
+            Ok(())
+        },
+    )?;
+    // values left behind by a program that exits early don't matter
+    if diverges || stack.vals.is_empty() {
+        return Ok(stack.warnings.take());
+    }
+    let left_by: Vec<String> = origins
+        .iter()
+        .filter_map(|i| locs.get(*i))
+        .map(Loc::to_string)
+        .collect();
+    Err(TypeCheckError::new(match left_by.as_slice() {
+        [one] => format!("1 excess value on the stack! it was left by the statement at {one}"),
+        _ => format!(
+            "{} excess values on the stack! they were left by the statements at {}",
+            stack.vals.len(),
+            left_by.join(", ")
+        ),
+    }))
 }
 
 pub fn check_interactive(
     stack: &mut TypeStack,
     statements: &Vec<Box<dyn Statement>>,
 ) -> Result<(), TypeCheckError> {
-    for s in statements {
-        s.in_pattern().type_check(stack)?;
-        s.custom_type_check(stack)?;
-        s.out_pattern().push(stack);
-    }
-    Ok(())
+    check_seq(
+        stack,
+        statements.iter().map(|s| s.as_ref()),
+        check_statement,
+    )
+    .map(|_| ())
 }
 
 pub fn check_rc(
     stack: &mut TypeStack,
     statements: &Vec<Rc<dyn Statement>>,
 ) -> Result<(), TypeCheckError> {
-    for s in statements {
-        s.in_pattern().type_check(stack)?;
-        s.custom_type_check(stack)?;
-        s.out_pattern().push(stack);
-    }
-    Ok(())
+    check_seq(
+        stack,
+        statements.iter().map(|s| s.as_ref()),
+        check_statement,
+    )
+    .map(|_| ())
 }
 
 impl StackPattern {
@@ -86,13 +235,13 @@ impl StackPattern {
     pub fn type_check(&self, stack: &mut TypeStack) -> Result<(), TypeCheckError> {
         for t in &self.0 {
             if let Some(actual) = stack.pop() {
-                if actual & t != actual {
-                    return Err(TypeCheckError(format!(
-                        "wrong type on stack when this executes"
-                    ))); // todo descriptive error msg
+                if !matches_type(actual, *t) {
+                    return Err(TypeCheckError::new(
+                        "wrong type on stack when this executes".into(),
+                    )); // todo descriptive error msg
                 }
             } else {
-                return Err(TypeCheckError(
+                return Err(TypeCheckError::new(
                     "too few values on stack when this executes".into(),
                 ));
             }
@@ -113,21 +262,98 @@ impl TypeStack {
             vals: Vec::new(),
             vars: HashMap::new(),
             funs: HashMap::new(),
+            objects: HashMap::new(),
+            strict: false,
+            warnings: Rc::default(),
+            low: 0,
+        }
+    }
+
+    /// an empty stack for checking a nested scope, like a function or list body,
+    /// that sees the bindings of this one
+    pub fn scope(&self) -> Self {
+        Self {
+            vals: Vec::new(),
+            vars: self.vars.clone(),
+            funs: self.funs.clone(),
+            objects: self.objects.clone(),
+            strict: self.strict,
+            warnings: self.warnings.clone(),
+            low: 0,
         }
     }
     pub fn pop(&mut self) -> Option<u32> {
-        self.vals.pop()
+        let val = self.vals.pop();
+        self.low = self.low.min(self.vals.len());
+        val
+    }
+
+    /// moves the value at the given offset from the head on top, like AliceStack::move_to_top
+    pub fn move_to_top(&mut self, offset: usize) {
+        let i = self.vals.len() - 1 - offset;
+        let val = self.vals.remove(i);
+        self.vals.push(val);
+        self.low = self.low.min(i);
+    }
+
+    pub fn clear(&mut self) {
+        self.vals.clear();
+        self.low = 0;
     }
 
     pub fn required_size(&self, size: usize) -> Result<(), TypeCheckError> {
         if self.vals.len() < size {
-            Err(TypeCheckError(
+            Err(TypeCheckError::new(
                 "too few elements on stack when this executes".into(),
             ))
         } else {
             Ok(())
         }
     }
+
+    /// the state after one of two alternatively executing branches, each checked against a
+    /// copy of self. the branches must leave the same types on the stack. bindings only
+    /// survive if both branches bind them with the same type, and a branch may not change
+    /// the type of a binding that existed before
+    pub fn merge_branches(
+        &mut self,
+        a: TypeStack,
+        b: &TypeStack,
+        what: &str,
+    ) -> Result<(), TypeCheckError> {
+        if a.vals != b.vals {
+            return Err(TypeCheckError::new(format!(
+                "{what} don't have equal affect on stack"
+            )));
+        }
+        merge_bindings(&mut self.vars, a.vars, &b.vars, "variable", what)?;
+        merge_bindings(&mut self.funs, a.funs, &b.funs, "function", what)?;
+        // the type bits identify a declaration, so knowing about more types is harmless
+        self.objects.extend(a.objects);
+        self.vals = a.vals;
+        self.low = self.low.min(a.low).min(b.low);
+        Ok(())
+    }
+}
+
+fn merge_bindings<T: PartialEq>(
+    before: &mut HashMap<String, T>,
+    a: HashMap<String, T>,
+    b: &HashMap<String, T>,
+    kind: &str,
+    what: &str,
+) -> Result<(), TypeCheckError> {
+    for (ident, ty) in a {
+        if b.get(&ident) == Some(&ty) {
+            before.insert(ident, ty);
+        } else if before.contains_key(&ident) {
+            // both branches contain all previous bindings
+            return Err(TypeCheckError::new(format!(
+                "{what} change the type of {kind} '{ident}' differently"
+            )));
+        }
+    }
+    Ok(())
 }
 
 impl PartialEq<&Self> for TypeStack {
@@ -136,18 +362,49 @@ impl PartialEq<&Self> for TypeStack {
     }
 }
 
-pub struct TypeCheckError(pub String);
+pub struct TypeCheckError {
+    pub msg: String,
+    /// where the error is in the source, if the statement that found it knows
+    pub loc: Option<Loc>,
+}
 
 impl From<TypeCheckError> for String {
     fn from(err: TypeCheckError) -> String {
-        err.0.clone()
+        err.msg
     }
 }
 
 impl TypeCheckError {
+    pub fn new(msg: String) -> Self {
+        Self { msg, loc: None }
+    }
+
+    /// the same error, located at the given position in the source
+    pub fn at(self, loc: &Loc) -> Self {
+        Self {
+            loc: Some(loc.clone()),
+            ..self
+        }
+    }
+
     pub fn prefix(&self, mut prefix: String) -> Self {
-        prefix.push_str(&self.0);
-        Self(prefix)
+        prefix.push_str(&self.msg);
+        Self {
+            msg: prefix,
+            loc: self.loc.clone(),
+        }
+    }
+
+    /// the error as an AliceError, located in the given file if the position is unknown
+    pub fn in_file(self, file: String) -> AliceError {
+        match self.loc {
+            Some(loc) => AliceError {
+                stage: Stage::Parsing,
+                msg: self.msg,
+                loc,
+            },
+            None => AliceError::in_file(Stage::Parsing, self.msg, file),
+        }
     }
 }
 
@@ -157,9 +414,40 @@ pub fn type_bit(val: &AliceVal) -> u32 {
         AliceVal::Bool(_) => BOOL,
         AliceVal::Int(_) => INT,
         AliceVal::Float(_) => FLOAT,
+        AliceVal::Char(_) => CHAR,
         AliceVal::Object(Some(o)) => o.type_hash,
         AliceVal::Object(None) => OBJECT,
         AliceVal::Function(_) => panic!("function should not be allowed on stack"),
+        AliceVal::List(Some(l)) => list_of(l.elem_type),
+        AliceVal::List(None) => LIST,
+    }
+}
+
+/// a value of the type of the given bits, the inverse of type_bit
+pub fn prototype(bits: u32) -> Result<AliceVal, String> {
+    match bits {
+        STRING => Ok(AliceVal::string()),
+        BOOL => Ok(AliceVal::bool()),
+        INT => Ok(AliceVal::int()),
+        FLOAT => Ok(AliceVal::float()),
+        CHAR => Ok(AliceVal::char()),
+        b if is_list(&b) => Ok(AliceVal::list(list_elem(b), Vec::new())),
+        b => Err(format!("no value of type {}", type_name(b))),
+    }
+}
+
+/// human readable name of the given type bits, for error messages
+pub fn type_name(bits: u32) -> String {
+    match bits {
+        STRING => TYPE_STRING.into(),
+        BOOL => TYPE_BOOL.into(),
+        INT => TYPE_INT.into(),
+        FLOAT => TYPE_FLOAT.into(),
+        CHAR => TYPE_CHAR.into(),
+        ANY => "any".into(),
+        b if is_list(&b) => format!("{TYPE_LIST} of {}", type_name(list_elem(b))),
+        b if is_object(&b) => TYPE_OBJECT.into(),
+        _ => "unknown".into(),
     }
 }
 
@@ -170,3 +458,99 @@ pub fn type_bit_any_allowed(name: &String) -> Result<u32, String> {
         Ok(type_bit(&AliceVal::for_type_name(name)?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::AliceLexer;
+    use crate::parser::AliceParser;
+
+    /// parses the source without type checking it
+    fn parse(src: &str) -> Vec<Box<dyn Statement>> {
+        let tokens = AliceLexer::new(src.into(), "test".into())
+            .tokenize()
+            .unwrap();
+        let mut statements = Vec::new();
+        AliceParser::new(tokens)
+            .parse_each(
+                |e| e.to_string(),
+                |s| {
+                    statements.push(s);
+                    Ok(())
+                },
+            )
+            .unwrap();
+        statements
+    }
+
+    #[test]
+    fn code_after_okexit_is_unreachable() {
+        let statements = parse(r#"okexit "x" println"#);
+        let warnings = check(&statements, &[], false).ok().unwrap();
+        assert_eq!(
+            warnings,
+            vec!["unreachable code: the statements after okexit never run"]
+        );
+        let err = check(&statements, &[], true).err().unwrap();
+        assert_eq!(
+            err.msg,
+            "unreachable code: the statements after okexit never run"
+        );
+    }
+
+    #[test]
+    fn values_left_by_an_exit_are_not_excess() {
+        assert!(check(&parse(r#""a" 0 exit"#), &[], true).is_ok());
+        assert!(check(&parse(r#""a" 0"#), &[], true).is_err());
+    }
+
+    #[test]
+    fn bool_matches_must_cover_both_values() {
+        let missing = parse(r#"true match { true { "yes" println } }"#);
+        let err = check(&missing, &[], false).err().unwrap();
+        assert!(err.msg.contains("match is not exhaustive"), "{}", err.msg);
+        let covered = parse(r#"true match { true { "yes" println } false { "no" println } }"#);
+        assert!(check(&covered, &[], false).is_ok());
+    }
+
+    #[test]
+    fn nested_ifs_must_keep_the_stack_as_is() {
+        let checks = |src: &str| check(&parse(src), &[], false).is_ok();
+        assert!(checks(
+            r#"true if { false if { "a" println } "b" println }"#
+        ));
+        assert!(checks(
+            "fun one -> int { 1 } true if { true if { one() drop } }"
+        ));
+        assert!(checks("1 true if { true if { 2 + } } println"));
+        assert!(!checks("true if { true if { 1 } }"));
+        assert!(!checks("true if { true if { 1 } drop }"));
+        assert!(!checks(r#"1 true if { true if { "x" } } drop"#));
+    }
+
+    #[test]
+    fn comparisons_promote_numbers_but_reject_strings() {
+        assert!(check(&parse("1.5 2 > drop 2 1.5 < drop"), &[], false).is_ok());
+        let err = check(&parse(r#"1.5 "x" > drop"#), &[], false)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.msg,
+            "can only arithmetically compare numbers, found float and string"
+        );
+    }
+
+    #[test]
+    fn any_is_neither_a_list_nor_an_object() {
+        assert!(!is_list(&ANY) && !is_object(&ANY));
+        for src in [
+            "fun f: any any -> int { + }",
+            "fun f: any any -> bool { contains }",
+            "fun f: any any { zip drop }",
+            "fun f: any { enumerate drop }",
+        ] {
+            let err = check(&parse(src), &[], false).err().unwrap();
+            assert!(err.msg.starts_with("in function 'f'"), "{}", err.msg);
+        }
+    }
+}
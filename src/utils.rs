@@ -5,3 +5,49 @@ use std::rc::Rc;
 pub fn box_to_rc(b: Box<dyn Statement>) -> Rc<dyn Statement> {
     Rc::from(b)
 }
+
+/// removes the `_` digit separators from a number in the given base, erroring on
+/// misplaced ones. a separator has to sit between two digits, i. e. `1_000` and
+/// `1e1_0` are fine while `_1`, `1_`, `1__0`, `1_.0`, `1_e1` and `1e_1` are not
+pub fn strip_digit_separators(s: &str, base: u32) -> Result<String, String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut stripped = String::with_capacity(s.len());
+    for (i, c) in chars.iter().enumerate() {
+        if *c != '_' {
+            stripped.push(*c);
+            continue;
+        }
+        let prev_ok = i > 0 && chars[i - 1].is_digit(base);
+        let next_ok = matches!(chars.get(i + 1), Some(n) if n.is_digit(base));
+        if !prev_ok || !next_ok {
+            return Err(format!("misplaced digit separator '_' in '{s}'"));
+        }
+    }
+    Ok(stripped)
+}
+
+/// the candidate closest to the given word by edit distance, if it is close enough
+/// to likely be a typo
+pub fn closest_match<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = word.chars().count().div_ceil(3).max(1);
+    candidates
+        .map(|c| (edit_distance(word, c), c))
+        .filter(|(d, _)| *d <= max_distance)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+/// levenshtein distance between the two strings
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let replace = prev[j] + usize::from(ca != *cb);
+            row[j + 1] = replace.min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
@@ -0,0 +1,815 @@
+//! runs alice programs through the interpreter binary and checks what they print
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// a fresh path in the temp dir, unique per test run and call
+fn temp_path(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::SeqCst);
+    std::env::temp_dir().join(format!("alice_test_{}_{n}_{name}", std::process::id()))
+}
+
+/// runs the given source as a file with the given flags before and program
+/// arguments after the path
+fn run_with(src: &str, flags: &[&str], args: &[&str]) -> Output {
+    let path = temp_path("program.alice");
+    std::fs::write(&path, src).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_alice_rs"))
+        .args(flags)
+        .arg(&path)
+        .args(args)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    output
+}
+
+fn run(src: &str) -> Output {
+    run_with(src, &[], &[])
+}
+
+/// feeds the given lines to the interactive mode
+fn run_interactive(flags: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_alice_rs"))
+        .args(flags)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// runs the source, asserting that it succeeds, and returns what it printed
+fn ok(src: &str) -> String {
+    let output = run(src);
+    assert!(
+        output.status.success(),
+        "program failed: {}\n{src}",
+        stderr(&output)
+    );
+    stdout(&output)
+}
+
+/// runs the source, asserting that it fails, and returns the error output
+fn err(src: &str) -> String {
+    let output = run(src);
+    assert!(
+        !output.status.success(),
+        "program succeeded: {}\n{src}",
+        stdout(&output)
+    );
+    stderr(&output)
+}
+
+#[test]
+fn examples() {
+    for entry in std::fs::read_dir("examples").unwrap() {
+        let path = entry.unwrap().path();
+        let output = Command::new(env!("CARGO_BIN_EXE_alice_rs"))
+            .arg(&path)
+            .output()
+            .unwrap();
+        // type_check.alice demonstrates a program rejected by the type checker
+        let should_fail = path.ends_with("type_check.alice");
+        assert_eq!(
+            output.status.success(),
+            !should_fail,
+            "{}: {}",
+            path.display(),
+            stderr(&output)
+        );
+    }
+}
+
+#[test]
+fn readfile_reads_back_what_writefile_wrote() {
+    let path = temp_path("file.txt");
+    let path = path.to_str().unwrap();
+    assert_eq!(
+        ok(&format!(
+            r#""{path}" "hello file" writefile "{path}" readfile println"#
+        )),
+        "hello file\n"
+    );
+    std::fs::remove_file(path).unwrap();
+    assert!(err(&format!(r#""{path}" readfile println"#)).contains("cannot read file"));
+}
+
+#[test]
+fn objects_are_equal_if_their_members_are() {
+    assert_eq!(
+        ok("object Point { x: int y: float }
+            3 1.5 Point() 3 1.5 Point() == println
+            3 1.5 Point() 4 1.5 Point() == println"),
+        "true\nfalse\n"
+    );
+}
+
+#[test]
+fn io_words_error_under_no_io() {
+    let path = temp_path("no_io.txt");
+    std::fs::write(&path, "content").unwrap();
+    let src = format!(r#""{}" readfile println"#, path.to_str().unwrap());
+    assert_eq!(ok(&src), "content\n");
+    let output = run_with(&src, &["--no-io"], &[]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("'readfile' is not allowed"));
+    // the interactive mode is sandboxed the same way
+    let output = run_interactive(&["--no-io"], &format!("{src}\n:load {}\n", path.display()));
+    assert!(stderr(&output).contains("'readfile' is not allowed"));
+    assert!(stderr(&output).contains("':load' is not allowed"));
+    assert!(!stdout(&output).contains("content"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn try_catch_handles_runtime_errors() {
+    assert_eq!(
+        ok("try { 1 0 / println } catch { println }"),
+        "division by zero\n"
+    );
+    // the body may have failed before binding s
+    assert!(
+        err(r#"try { "nofile" readfile let s: string } catch { drop } s println"#)
+            .contains("unknown word or variable 's'")
+    );
+}
+
+#[test]
+fn recursion_is_limited() {
+    assert!(err("fun f { f() } f()").contains("maximum recursion depth exceeded"));
+    assert_eq!(
+        ok("fun f { f() } try { f() } catch { println }"),
+        "maximum recursion depth exceeded\n"
+    );
+    let src =
+        "fun down: int { dup 0 > if { 1 - down() } else { drop } } 50 down() \"done\" println";
+    assert_eq!(ok(src), "done\n");
+    let output = run_with(src, &["--max-call-depth", "10"], &[]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("maximum recursion depth exceeded"));
+}
+
+#[test]
+fn args_are_passed_to_the_program() {
+    let output = run_with("args println", &[], &["foo", "-x", "--bar"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "[foo -x --bar]\n");
+}
+
+#[test]
+fn nested_list_types_are_distinct() {
+    assert!(err(r#"[[[[1]]]] [[[["a"]]]] == println"#).contains("different types"));
+    assert_eq!(
+        ok("object Point { x: int y: float }
+            [3 1.5 Point()] 3 1.5 Point() contains println"),
+        "true\n"
+    );
+}
+
+#[test]
+fn deeply_nested_json_is_an_error() {
+    let src = format!(r#""{}" fromjson@[int] println"#, "[".repeat(200_000));
+    assert!(err(&src).contains("invalid json: nested deeper than"));
+}
+
+#[test]
+fn deeply_nested_list_types_are_an_error() {
+    let src = format!(
+        "1 @{}int{} println",
+        "[".repeat(200_000),
+        "]".repeat(200_000)
+    );
+    assert!(err(&src).contains("list types are nested deeper than"));
+}
+
+#[test]
+fn assertstack_compares_like_equals() {
+    assert_eq!(
+        ok("object Point { x: int y: float }
+            1 2 [1.0 2.0] assertstack drop drop
+            3 1.5 Point() [3 1.5 Point()] assertstack drop
+            \"passed\" println"),
+        "passed\n"
+    );
+    let e = err("object Point { x: int y: float }
+        3 1.5 Point() [3 2.5 Point()] assertstack drop");
+    assert!(e.contains("stack assertion failed"), "{e}");
+    assert!(e.contains("at 0: expected"), "{e}");
+}
+
+#[test]
+fn negative_int_exponents() {
+    // the same rule applies however the exponent was written
+    for src in [
+        "2 -1 ** println",
+        "2 0 1 - ** println",
+        "-1 let e: int 2 e ** println",
+    ] {
+        assert!(err(src).contains("negative exponent in 2 ** -1"), "{src}");
+        let output = run_with(src, &["--pow-overflow-float"], &[]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert_eq!(stdout(&output), "0.5\n");
+    }
+    assert_eq!(ok("2@float -1 ** println"), "0.5\n");
+}
+
+#[test]
+fn aliases_stand_for_their_word() {
+    assert_eq!(ok("alias plus + 1 2 plus println"), "3\n");
+    // in the interactive mode, aliases are kept from one line to the next
+    let output = run_interactive(&[], "alias plus +\n1 2 plus println\n");
+    assert_eq!(stderr(&output), "");
+    assert!(stdout(&output).contains("3\n"));
+}
+
+#[test]
+fn zip_and_enumerate_make_pairs() {
+    assert_eq!(
+        ok(r#"[1 2] ["a" "b"] zip println"#),
+        "[Pair { first: 1, second: a } Pair { first: 2, second: b }]\n"
+    );
+    assert_eq!(
+        ok(r#"["x" "y"] enumerate println"#),
+        "[Pair { first: 0, second: x } Pair { first: 1, second: y }]\n"
+    );
+}
+
+#[test]
+fn unknown_words_in_function_bodies_are_located() {
+    let e = err("fun greet {\n    \"hi\" prntln\n}\ngreet()");
+    assert!(
+        e.contains(":2:10: error parsing: in function 'greet': unknown word or variable 'prntln', did you mean 'println'?"),
+        "{e}"
+    );
+}
+
+#[test]
+fn excess_values_are_located() {
+    let e = err("1 \"a\"\n2 3 swap\ndrop drop");
+    assert!(
+        e.contains("2 excess values on the stack! they were left by the statements at"),
+        "{e}"
+    );
+    assert!(e.contains(".alice:1:1, "), "{e}");
+    assert!(e.contains(".alice:1:3\""), "{e}");
+}
+
+#[test]
+fn object_members_take_their_defaults() {
+    assert_eq!(
+        ok("object Point { x: int = 0 y: int = 0 }
+            Point(x: 5) println
+            Point(x: 5) .y println"),
+        "Point { x: 5, y: 0 }\n0\n"
+    );
+    assert!(err(r#"object Point { x: int = "0" }"#)
+        .contains("member x of object Point is int, but its default 0 is string"));
+    assert!(
+        err("object Point { x: int y: int = 0 } Point(y: 1) println")
+            .contains("member x of Point has no default, so it has to be given")
+    );
+}
+
+#[test]
+fn equal_seeds_give_equal_rand_sequences() {
+    let src = "rand println rand println 100 rand println";
+    let first = run_with(src, &["--seed", "42"], &[]);
+    let second = run_with(src, &["--seed", "42"], &[]);
+    assert!(first.status.success(), "{}", stderr(&first));
+    assert_eq!(stdout(&first), stdout(&second));
+    let other = run_with(src, &["--seed", "43"], &[]);
+    assert_ne!(stdout(&first), stdout(&other));
+}
+
+#[test]
+fn toint_accepts_digit_separators() {
+    assert_eq!(
+        ok(r#""1_000" toint println "1_000" @int println"#),
+        "1000\n1000\n"
+    );
+    assert!(err(r#""1__0" toint println"#).contains("misplaced digit separator"));
+}
+
+#[test]
+fn dumpjson_tags_each_value_with_its_type() {
+    assert_eq!(
+        ok(r#"1 "a" 2.5 true dumpjson clear"#),
+        r#"[{"type":"int","value":1},{"type":"string","value":"a"},{"type":"float","value":2.5},{"type":"bool","value":true}]"#
+            .to_string()
+            + "\n"
+    );
+}
+
+#[test]
+fn conversions_follow_any_expression() {
+    assert_eq!(ok("let x: int = 5 x @float println"), "5\n");
+}
+
+#[test]
+fn repl_values_keep_their_types_across_lines() {
+    let output = run_interactive(&[], "3\n4 +\nprintln\n");
+    assert!(stdout(&output).contains("7\n"), "{}", stdout(&output));
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn saved_functions_can_be_loaded_again() {
+    let path = temp_path("saved.alice");
+    let path = path.to_str().unwrap();
+    let output = run_interactive(
+        &[],
+        &format!("fun sq: int -> int {{ dup * }}\n:save {path}\n"),
+    );
+    assert_eq!(stderr(&output), "");
+    assert_eq!(
+        std::fs::read_to_string(path).unwrap(),
+        "fun sq: int -> int { dup * }\n"
+    );
+    let output = run_interactive(&[], &format!(":load {path}\n3 sq() println\n"));
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(stderr(&output), "");
+    assert!(stdout(&output).contains("9\n"), "{}", stdout(&output));
+}
+
+#[test]
+fn repl_clear_forgets_the_checked_types() {
+    let output = run_interactive(&[], "1 2\nclear\n\"a\" println\n1 +\n");
+    assert!(stdout(&output).contains("a\n"), "{}", stdout(&output));
+    assert!(
+        stderr(&output).contains("too few elements on stack"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn ints_and_floats_compare_by_value() {
+    assert_eq!(
+        ok("2 2.0 == println 2 2.5 == println 2.0 2 == println"),
+        "true\nfalse\ntrue\n"
+    );
+}
+
+#[test]
+fn chained_conversions_apply_in_order() {
+    assert_eq!(
+        ok("5@float@int 2 / println 5@int@float 2 / println"),
+        "2\n2.5\n"
+    );
+}
+
+#[test]
+fn numeric_limit_words() {
+    assert_eq!(
+        ok("intmax println intmin println floatinf println floatinf floatmax > println"),
+        format!("{}\n{}\ninf\ntrue\n", i64::MAX, i64::MIN)
+    );
+}
+
+#[test]
+fn blocks_parse_like_the_top_level() {
+    for src in [
+        "1 2 + println",
+        "5@float 2 / println",
+        "\"a\" println # comment",
+        "1 ; 2 + println",
+        "/* comment */ 3 println",
+        "let x: int = 4 x println",
+        "true if { 1 println }",
+    ] {
+        assert_eq!(ok(src), ok(&format!("true if {{\n{src}\n}}")), "{src}");
+    }
+    assert!(err(")").contains("unexpected separator ')'"));
+    assert!(err("true if { ) }").contains("unexpected separator ')'"));
+}
+
+#[test]
+fn function_bodies_see_the_bindings_of_their_scope() {
+    assert_eq!(
+        ok("let n: int = 3 fun f -> int { n 1 + } f() println"),
+        "4\n"
+    );
+    assert_eq!(ok("fun sq: int -> int { dup * } 4 sq() println"), "16\n");
+    assert!(err("fun f -> int { m } f() println").contains("in function 'f'"));
+}
+
+#[test]
+fn interpolated_variables_are_type_checked() {
+    assert_eq!(ok(r#"let n: int = 3 "n is ${n}" println"#), "n is 3\n");
+    assert!(err(r#""hi ${nope}" println"#)
+        .contains("interpolation '${nope}': variable binding nope doesn't exist"));
+}
+
+/// how often statements of the given kind ran according to the output of --profile
+fn profile_count(out: &str, kind: &str) -> Option<usize> {
+    let line = out
+        .lines()
+        .find(|l| l.split_whitespace().nth(1) == Some(kind))?;
+    line.split_whitespace()
+        .nth(2)?
+        .strip_suffix('x')?
+        .parse()
+        .ok()
+}
+
+#[test]
+fn profile_counts_executions_per_kind() {
+    let output = run_with("for i in 0..3 { i println }", &["--profile"], &[]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = stdout(&output);
+    assert_eq!(profile_count(&out, "ForStatement"), Some(1), "{out}");
+    assert_eq!(profile_count(&out, "PrintlnStatement"), Some(3), "{out}");
+    assert_eq!(
+        profile_count(&out, "PushFromTableStatement"),
+        Some(3),
+        "{out}"
+    );
+}
+
+#[test]
+fn trace_shows_where_values_were_pushed() {
+    let output = run_with(r#"let x: int = 1 x "s" pstack clear"#, &["--trace"], &[]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "1\t(pushed by PushFromTableStatement in statement 2)\n\
+         s\t(pushed by PushStatement in statement 3)\n"
+    );
+}
+
+#[test]
+fn abort_prints_the_message_and_exits_with_the_code() {
+    let output = run(r#""still here" println "boom" 3 abort "unreachable" println"#);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "still here\n");
+    assert!(stderr(&output).ends_with("boom\n"), "{}", stderr(&output));
+}
+
+#[test]
+fn capacities_can_be_set_on_the_command_line() {
+    let output = run_with(
+        "1 2 + println",
+        &["--stack-capacity", "1", "--table-capacity", "1"],
+        &[],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "3\n");
+}
+
+#[test]
+fn arithmetic_promotes_ints_to_floats() {
+    assert_eq!(
+        ok("7 2 + println 7 2.5 + println 7.5 2 - println 7 2 * println 2 1.5 * println"),
+        "9\n9.5\n5.5\n14\n3\n"
+    );
+    assert_eq!(
+        ok("7 2 / println 7.0 2 / println 7 2 % println 7.5 2 % println 7 2.0 % println"),
+        "3\n3.5\n1\n1.5\n1\n"
+    );
+    assert!(err("1 0 / println").contains("division by zero"));
+    assert!(err("1 0 % println").contains("division by zero"));
+}
+
+#[test]
+fn lexer_and_parser_errors_share_a_format() {
+    assert!(err("1 println\n  \"abc")
+        .contains(".alice:2:3: error tokenizing: missing string delimiter"));
+    assert!(err("1 println\n  nope")
+        .contains(".alice:2:3: error parsing: unknown word or variable 'nope'"));
+}
+
+#[test]
+fn int_pow_overflow_errors_or_promotes_under_the_flag() {
+    assert!(err("10 20 ** println").contains("integer overflow: 10 ** 20 doesn't fit into an int"));
+    let output = run_with(
+        "10 20 ** println 2 10 ** println",
+        &["--pow-overflow-float"],
+        &[],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "100000000000000000000\n1024\n");
+}
+
+#[test]
+fn len_counts_chars_and_bytelen_bytes() {
+    assert_eq!(
+        ok(r#""héllo" len println "héllo" bytelen println "abc" len println"#),
+        "5\n6\n3\n"
+    );
+}
+
+#[test]
+fn nested_lists_round_trip_through_json() {
+    assert_eq!(
+        ok("[[1 2] [3]] tojson dup println fromjson@[[int]] println"),
+        "[[1,2],[3]]\n[[1 2] [3]]\n"
+    );
+    assert_eq!(
+        ok("[[1 2] [3]] dup tojson fromjson@[[int]] == println"),
+        "true\n"
+    );
+}
+
+#[test]
+fn let_bindings_take_the_value_of_a_block() {
+    assert_eq!(ok("let x: int = { 2 3 + } x println"), "5\n");
+    assert!(err("let x: int = { 2 3 } x println").contains("has to push exactly one value"));
+    assert!(err(r#"let x: int = { "a" } x println"#).contains("pushes string instead of int"));
+}
+
+#[test]
+fn list_arithmetic_is_element_wise() {
+    assert_eq!(
+        ok("[1 2 3] [4 5 6] + println [1.5 2.0] [2.0 2.0] * println"),
+        "[5 7 9]\n[3 4]\n"
+    );
+    assert!(err("[1 2] [4 5 6] + println")
+        .contains("element-wise arithmetic on lists of different lengths 2 and 3"));
+}
+
+#[test]
+fn repl_runs_the_statements_before_a_bad_one() {
+    let output = run_interactive(&[], "1 println garbage 2 println\n");
+    assert!(stdout(&output).contains("1\n"), "{}", stdout(&output));
+    assert!(!stdout(&output).contains('2'), "{}", stdout(&output));
+    assert!(stderr(&output)
+        .contains("<interactive>:1:11: error parsing: unknown word or variable 'garbage'"));
+}
+
+#[test]
+fn memoized_functions_run_once_per_argument() {
+    let fib = "fun fib: int -> int {\n    dup 2 < if { } else { dup 1 - fib() swap 2 - fib() + }\n}\n15 fib() println";
+    let plain = run_with(fib, &["--profile"], &[]);
+    let memoized = run_with(&format!("memoize {fib}"), &["--profile"], &[]);
+    assert!(stdout(&plain).starts_with("610\n"), "{}", stderr(&plain));
+    assert!(
+        stdout(&memoized).starts_with("610\n"),
+        "{}",
+        stderr(&memoized)
+    );
+    // the body starts with the only <
+    assert_eq!(profile_count(&stdout(&plain), "LtStatement"), Some(1973));
+    assert_eq!(profile_count(&stdout(&memoized), "LtStatement"), Some(16));
+}
+
+#[test]
+fn block_comments_are_skipped() {
+    assert_eq!(ok("1 println\n/* a\n b */\n2 println"), "1\n2\n");
+}
+
+#[test]
+fn clamp_keeps_values_within_bounds() {
+    assert_eq!(
+        ok("0 1 10 clamp println 5 1 10 clamp println 11 1 10 clamp println 2.5 1 10 clamp println"),
+        "1\n5\n10\n2.5\n"
+    );
+    assert!(err("5 10 1 clamp println").contains("lower bound 10 is above upper bound 1"));
+}
+
+#[test]
+fn repl_runs_statements_separated_by_semicolons() {
+    let output = run_interactive(&[], "3 println; 4 println\n");
+    assert_eq!(stderr(&output), "");
+    assert!(stdout(&output).contains("3\n4\n"), "{}", stdout(&output));
+}
+
+#[test]
+fn fields_lists_members_sorted_by_name() {
+    assert_eq!(
+        ok("object P { z: int a: float m: string } 1 2.0 \"s\" P() fields println"),
+        "[a m z]\n"
+    );
+}
+
+#[test]
+fn reverse_and_contains() {
+    assert_eq!(
+        ok(
+            r#"[1 2 3] reverse println "abc" reverse println [1 2 3] 2 contains println [1 2 3] 5 contains println"#
+        ),
+        "[3 2 1]\ncba\ntrue\nfalse\n"
+    );
+}
+
+#[test]
+fn indexof_counts_chars() {
+    assert_eq!(
+        ok(
+            r#""héllo wörld" "w" indexof println "abcabc" "ca" indexof println "abc" "z" indexof println"#
+        ),
+        "6\n2\n-1\n"
+    );
+}
+
+#[test]
+fn replace_replaces_non_overlapping_occurrences() {
+    assert_eq!(
+        ok(
+            r#""a.b.c" "." "-" replace println "aaaa" "aa" "b" replace println "aaa" "aa" "b" replace println"#
+        ),
+        "a-b-c\nbb\nba\n"
+    );
+    assert!(err(r#""abc" "" "x" replace println"#).contains("cannot replace an empty pattern"));
+}
+
+#[test]
+fn match_arms_bind_the_members_of_their_object_type() {
+    let describe = "object Point { x: int y: int }
+        object Circle { r: float }
+        fun describe: any {
+            match {
+                Point { x y + println }
+                Circle { \"circle of radius ${r}\" println }
+                _ { \"something else\" println }
+            }
+        }
+        let x: string = \"outer\"";
+    assert_eq!(
+        ok(&format!(
+            "{describe} 1 2 Point() describe() 2.5 Circle() describe() \"text\" describe() x println"
+        )),
+        "3\ncircle of radius 2.5\nsomething else\nouter\n"
+    );
+    // an arm for the type of the matched object makes the match exhaustive
+    assert_eq!(
+        ok("object Point { x: int } 4 Point() match { Point { x println } }"),
+        "4\n"
+    );
+    assert!(
+        err("object P { x: int } object C { r: float } 1 P() match { C { } _ { } }")
+            .contains("match arm C has type C but the matched value has type P")
+    );
+    assert!(err("object P { x: int } 1 P() match { P { } } x println")
+        .contains("unknown word or variable 'x'"));
+}
+
+#[test]
+fn rotn_generalizes_rot() {
+    assert_eq!(
+        ok("1 2 3 3 rotn pstack clear 1 2 3 rot pstack clear"),
+        "2\n3\n1\n2\n3\n1\n"
+    );
+    assert_eq!(ok("1 2 3 4 4 rotn pstack clear"), "2\n3\n4\n1\n");
+    assert!(err("1 2 5 rotn").contains("too few elements on stack"));
+}
+
+#[test]
+fn repl_doc_prints_the_doc_comment_of_a_function() {
+    let output = run_interactive(
+        &[],
+        "/** squares an int */ fun sq: int -> int { dup * }\n:doc sq\nfun id: int -> int { }\n:doc id\n",
+    );
+    assert!(
+        stdout(&output).contains("squares an int\n"),
+        "{}",
+        stdout(&output)
+    );
+    assert!(stderr(&output).contains("function 'id' is not documented"));
+}
+
+#[test]
+fn fixed_and_scientific_notation() {
+    assert_eq!(
+        ok("3.14159 2 tofixed println 0.5 0 tofixed println 12345.678 2 toscientific println"),
+        "3.14\n0\n1.23e4\n"
+    );
+}
+
+// objects have no methods yet, so chains only cover members. a chain ending in a
+// method call, like `.i .method()`, belongs here once methods exist
+#[test]
+fn member_access_chains_through_object_members() {
+    let decls = "object In { v: int } object Out { i: In n: [int] }";
+    assert_eq!(
+        ok(&format!(
+            "{decls} 5 In() [1 2] Out() dup .i .v println dup .n println println"
+        )),
+        "5\n[1 2]\nOut { i: In { v: 5 }, n: [1 2] }\n"
+    );
+    assert_eq!(
+        ok(&format!(
+            "{decls} 5 In() [1 2] Out() tojson fromjson@Out .i .v println"
+        )),
+        "5\n"
+    );
+    // object types are known from their declaration on
+    assert!(err("object Out { i: In } object In { v: int }")
+        .contains("member i of object Out: unexpected token 'In' that is not a type"));
+    // also across lines of the interactive mode
+    let output = run_interactive(
+        &[],
+        "object In { v: int }\nobject Out { i: In }\n5 In() Out() .i .v println\n",
+    );
+    assert_eq!(stderr(&output), "");
+    assert!(stdout(&output).contains("5\n"), "{}", stdout(&output));
+}
+
+#[test]
+fn while_counts_down_to_zero() {
+    assert_eq!(
+        ok("3 let n: int
+            while { n 0 > } {
+                n println
+                n 1 - let n: int
+            }
+            \"liftoff!\" println"),
+        "3\n2\n1\nliftoff!\n"
+    );
+    assert!(err("while { 1 } { }").contains("bool"));
+}
+
+#[test]
+fn count_counts_trues() {
+    assert_eq!(
+        ok("[true false true] count println true @int println false@int println"),
+        "2\n1\n0\n"
+    );
+}
+
+#[test]
+fn let_with_a_literal_pops_nothing() {
+    assert_eq!(ok("let x: int = 5 x println"), "5\n");
+    assert_eq!(ok("1 let x: float = 2.5 x println println"), "2.5\n1\n");
+}
+
+#[test]
+fn assertstack_checks_the_stack_left_by_a_script() {
+    assert_eq!(
+        ok("for i in 0..4 { } 1 2 3 + 4 5 *
+            [1 5 20] assertstack
+            drop drop drop
+            \"ok\" println"),
+        "ok\n"
+    );
+    let e = err("1 2 [1 2 3] assertstack drop drop");
+    assert!(e.contains("expected 3 values, found 2"), "{e}");
+    assert!(e.contains("at 2: expected 3 (int), found nothing"), "{e}");
+}
+
+#[test]
+fn objects_round_trip_through_json() {
+    assert_eq!(
+        ok("object P { x: int y: float } 3 1.5 P() dup tojson dup println fromjson@P == println"),
+        "{\"type\":\"P\",\"value\":{\"x\":3,\"y\":1.5}}\ntrue\n"
+    );
+    assert!(err(r#""{}" fromjson@Q"#).contains("unknown object type Q"));
+    assert!(err(
+        r#"object P { x: int } "{\"type\":\"Q\",\"value\":{\"x\":1}}" fromjson@P println"#
+    )
+    .contains("expected json for P, found Q"));
+}
+
+#[test]
+fn int_overflow_is_an_error() {
+    assert!(err("intmax 1 + println").contains("integer overflow in +"));
+    assert!(err("intmin 1 - println").contains("integer overflow in -"));
+    assert!(err("intmax 2 * println").contains("integer overflow in *"));
+    assert!(err("3 40 ** println").contains("integer overflow: 3 ** 40 doesn't fit into an int"));
+    assert_eq!(ok("intmax 1 - 1 + intmax == println"), "true\n");
+}
+
+#[test]
+fn number_predicates() {
+    assert_eq!(
+        ok("4 iseven println 3 iseven println -3 isodd println 2 isodd println"),
+        "true\nfalse\ntrue\nfalse\n"
+    );
+    assert_eq!(
+        ok("0 iszero println 0.0 iszero println 0.1 iszero println \
+            -2 isnegative println 0 isnegative println \
+            2.5 ispositive println 0 ispositive println -0.5 ispositive println"),
+        "true\ntrue\nfalse\ntrue\nfalse\ntrue\nfalse\nfalse\n"
+    );
+    assert!(err("2.5 iseven println").contains("wrong type on stack"));
+}
+
+#[test]
+fn slice_takes_a_sublist() {
+    assert_eq!(
+        ok("[1 2 3 4] 1 3 slice println [1 2 3] 0 0 slice println [1 2 3] 0 3 slice println"),
+        "[2 3]\n[]\n[1 2 3]\n"
+    );
+    assert!(
+        err("[1 2 3] 2 5 slice println").contains("slice 2..5 out of bounds for list of length 3")
+    );
+    assert!(err("[1 2 3] 2 1 slice println").contains("slice 2..1 out of bounds"));
+}